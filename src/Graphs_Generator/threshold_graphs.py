@@ -0,0 +1,39 @@
+# um grafo threshold é construído adicionando, um a um, vértices isolados
+# ou dominantes (ligados a todos os anteriores), a "sequência de criação"
+# sorteamos essa sequência e escrevemos a matriz de adjacência, igual aos completos
+
+import random
+
+import numpy as np
+
+print("---- Gerador de Grafos Threshold em Matriz de Adjacência ----")
+
+n = input("Informe quantidade de vértices: ")
+nome = "threshold_"+n+".txt"
+
+arq = open(nome, "w")
+
+# embaralhamos os rótulos, pra ordem de criação não ser sempre 0, 1, 2, ...
+ordem = list(range(0,int(n)))
+random.shuffle(ordem)
+
+grafo = np.zeros((int(n),int(n)), dtype=np.short)
+sequencia = ""
+for i in range(0,int(n)):
+    v = ordem[i]
+    if i > 0 and random.random() < 0.5:
+        sequencia += "d"
+        for j in range(0,i):
+            grafo[v,ordem[j]] = 1
+            grafo[ordem[j],v] = 1
+    else:
+        sequencia += "i"
+print("Sequência de criação: "+sequencia)
+print(str(grafo))
+
+for i in range(0,int(n)):
+    for j in range(0,int(n)):
+        arq.write(str(grafo[i,j])+" ")
+    arq.write("\n")
+
+arq.close()
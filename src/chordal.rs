@@ -0,0 +1,742 @@
+use std::borrow::Cow;
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::clique_tree::CliqueTree;
+use crate::cograph::Cotree;
+use crate::common::{induced_subgraph, scheme_positions, ForbiddenSubgraph, NotChordal};
+use crate::serial::{find_hole, hole_from_scheme, is_chordal_with_certificate, is_pes};
+
+type Graph = Csr<(), (), Undirected>;
+
+// Makes sure we have a PES to work with
+// If the given scheme isn't one, either the graph has a hole, which we report,
+// or the scheme was just a bad one, and we replace it by a LexBFS scheme
+pub(crate) fn perfect_scheme<'a>(
+    graph: &Graph,
+    scheme: &'a [i32],
+) -> Result<Cow<'a, [i32]>, NotChordal> {
+    if is_pes(scheme, graph) {
+        return Ok(Cow::Borrowed(scheme));
+    }
+
+    if let Some(hole) = hole_from_scheme(scheme, graph).or_else(|| find_hole(graph)) {
+        return Err(NotChordal { hole });
+    }
+
+    is_chordal_with_certificate(graph).map(Cow::Owned)
+}
+
+// For each vertex of a scheme, its later neighbours, in elimination order
+// Going through the scheme, each vertex is appended to the lists of its earlier neighbours,
+// which keeps them sorted without comparing anything. This is O(n + m)
+pub(crate) fn later_neighbours(graph: &Graph, scheme: &[i32]) -> Vec<Vec<u32>> {
+    let position = scheme_positions(scheme);
+    let mut later = vec![Vec::new(); scheme.len()];
+
+    for &w in scheme {
+        for &v in graph.neighbors_slice(w as u32) {
+            if position[v as usize] < position[w as usize] {
+                later[position[v as usize]].push(w as u32);
+            }
+        }
+    }
+
+    later
+}
+
+// Sorts every list of vertices, by bucketing their entries by vertex and then reading the
+// buckets in order. This is O(n + k), k being the total length of the lists
+pub(crate) fn sort_vertex_lists(n: usize, lists: Vec<Vec<u32>>) -> Vec<Vec<u32>> {
+    let mut lists_of = vec![Vec::new(); n];
+    for (index, list) in lists.iter().enumerate() {
+        for &v in list {
+            lists_of[v as usize].push(index);
+        }
+    }
+
+    let mut sorted: Vec<Vec<u32>> = lists
+        .iter()
+        .map(|list| Vec::with_capacity(list.len()))
+        .collect();
+    for (v, indices) in lists_of.into_iter().enumerate() {
+        for index in indices {
+            sorted[index].push(v as u32);
+        }
+    }
+
+    sorted
+}
+
+// Fulkerson and Gross: in a PES, every maximal clique is some vertex v together with
+// its later neighbours. That set is not maximal iff some child u of v (v being
+// the earliest later neighbour of u) has exactly one more later neighbour than v,
+// in which case it is absorbed by the clique of u
+// Returns, for each position of the scheme, the position of such a child, if any
+pub(crate) fn absorbed_by(later: &[Vec<u32>], position: &[usize]) -> Vec<Option<usize>> {
+    let mut absorbed_by = vec![None; later.len()];
+
+    for (i, neighbours) in later.iter().enumerate() {
+        if let Some(&parent) = neighbours.first() {
+            let parent = position[parent as usize];
+
+            if later[parent].len() + 1 == neighbours.len() {
+                absorbed_by[parent] = Some(i);
+            }
+        }
+    }
+
+    absorbed_by
+}
+
+// Cliques are listed in the order of the scheme, each one sorted. This is O(n + m)
+pub fn maximal_cliques(graph: &Graph, scheme: &[i32]) -> Result<Vec<Vec<u32>>, NotChordal> {
+    let scheme = perfect_scheme(graph, scheme)?;
+    let later = later_neighbours(graph, &scheme);
+    let absorbed_by = absorbed_by(&later, &scheme_positions(&scheme));
+
+    let cliques = scheme
+        .iter()
+        .zip(later)
+        .zip(absorbed_by)
+        .filter(|&(_, absorbed_by)| absorbed_by.is_none())
+        .map(|((&v, mut clique), _)| {
+            clique.push(v as u32);
+
+            clique
+        })
+        .collect();
+
+    Ok(sort_vertex_lists(scheme.len(), cliques))
+}
+
+// ω(G), the size of a largest clique
+pub fn clique_number(graph: &Graph) -> Result<usize, NotChordal> {
+    let scheme = is_chordal_with_certificate(graph)?;
+
+    let omega = later_neighbours(graph, &scheme)
+        .iter()
+        .map(|later| later.len() + 1)
+        .max()
+        .unwrap_or(0);
+
+    Ok(omega)
+}
+
+// Greedily colours the vertices in reverse PES order, each one with the smallest colour
+// not used by its later neighbours. Those form a clique, so no more than ω(G) colours
+// are ever used, which is optimal. This is O(n + m)
+pub fn color_chordal(graph: &Graph) -> Result<Vec<u32>, NotChordal> {
+    let scheme = is_chordal_with_certificate(graph)?;
+    let position = scheme_positions(&scheme);
+
+    let mut color = vec![0; scheme.len()];
+    // used[c] == v iff colour c is taken by a later neighbour of v
+    let mut used = vec![u32::MAX; scheme.len() + 1];
+
+    for &v in scheme.iter().rev() {
+        let later = graph
+            .neighbors_slice(v as u32)
+            .iter()
+            .filter(|&&w| position[w as usize] > position[v as usize]);
+
+        for &w in later {
+            used[color[w as usize] as usize] = v as u32;
+        }
+
+        color[v as usize] = (0..).find(|&c| used[c as usize] != v as u32).unwrap();
+    }
+
+    Ok(color)
+}
+
+// χ(G), which equals ω(G) for chordal graphs
+pub fn chromatic_number(graph: &Graph) -> Result<usize, NotChordal> {
+    let color = color_chordal(graph)?;
+
+    Ok(color.iter().map(|&c| c as usize + 1).max().unwrap_or(0))
+}
+
+// Gavril's algorithms, which share a single pass over the PES
+// Every vertex not yet covered goes into the independent set, and it covers itself and its
+// uncovered later neighbours with a clique. The independent set and the clique cover end up
+// with the same size, which proves both optimal (α(G) = θ(G) in perfect graphs)
+// Cliques are sorted, and given in the same order as the independent set. This is O(n + m)
+pub fn independent_set_and_clique_cover(
+    graph: &Graph,
+    scheme: &[i32],
+) -> Result<(Vec<u32>, Vec<Vec<u32>>), NotChordal> {
+    let scheme = perfect_scheme(graph, scheme)?;
+    let later = later_neighbours(graph, &scheme);
+
+    let mut covered = vec![false; scheme.len()];
+    let mut independent_set = Vec::new();
+    let mut clique_cover = Vec::new();
+
+    for (&v, later) in scheme.iter().zip(later) {
+        if covered[v as usize] {
+            continue;
+        }
+
+        let mut clique: Vec<u32> = later
+            .into_iter()
+            .filter(|&w| !covered[w as usize])
+            .collect();
+        clique.push(v as u32);
+
+        for &w in &clique {
+            covered[w as usize] = true;
+        }
+
+        independent_set.push(v as u32);
+        clique_cover.push(clique);
+    }
+
+    let clique_cover = sort_vertex_lists(scheme.len(), clique_cover);
+
+    debug_assert!(is_independent_set_and_clique_cover(
+        graph,
+        &independent_set,
+        &clique_cover
+    ));
+
+    Ok((independent_set, clique_cover))
+}
+
+// Checks the certificate of optimality: the set is independent, every member of the cover is
+// a clique, the cover partitions the vertices, each clique holds a different vertex of the set,
+// and both have the same size, which bounds each one by the other. Cliques are checked against
+// their marked vertices. This is O(n + m)
+pub fn is_independent_set_and_clique_cover(
+    graph: &Graph,
+    independent_set: &[u32],
+    clique_cover: &[Vec<u32>],
+) -> bool {
+    let n = graph.node_count();
+    let in_range = |v: &u32| (*v as usize) < n;
+
+    if independent_set.len() != clique_cover.len()
+        || !independent_set.iter().all(in_range)
+        || !clique_cover.iter().flatten().all(in_range)
+    {
+        return false;
+    }
+
+    let mut in_set = vec![false; n];
+    for &v in independent_set {
+        if in_set[v as usize] {
+            return false;
+        }
+        in_set[v as usize] = true;
+    }
+    let independent = independent_set.iter().all(|&v| {
+        graph
+            .neighbors_slice(v)
+            .iter()
+            .all(|&w| !in_set[w as usize])
+    });
+
+    // the clique each vertex is in, which must be exactly one
+    let mut clique_of = vec![usize::MAX; n];
+    for (index, clique) in clique_cover.iter().enumerate() {
+        for &v in clique {
+            if clique_of[v as usize] != usize::MAX {
+                return false;
+            }
+            clique_of[v as usize] = index;
+        }
+    }
+    let partition = clique_of.iter().all(|&index| index != usize::MAX);
+    if !partition {
+        return false;
+    }
+
+    let mut hit = vec![false; clique_cover.len()];
+    for &v in independent_set {
+        let index = clique_of[v as usize];
+        if hit[index] {
+            return false;
+        }
+        hit[index] = true;
+    }
+
+    let cliques = clique_cover.iter().enumerate().all(|(index, clique)| {
+        clique.iter().all(|&v| {
+            let inside = graph
+                .neighbors_slice(v)
+                .iter()
+                .filter(|&&w| clique_of[w as usize] == index)
+                .count();
+
+            inside + 1 == clique.len()
+        })
+    });
+
+    independent && cliques
+}
+
+pub fn maximum_independent_set(graph: &Graph, scheme: &[i32]) -> Result<Vec<u32>, NotChordal> {
+    independent_set_and_clique_cover(graph, scheme).map(|(independent_set, _)| independent_set)
+}
+
+pub fn minimum_clique_cover(graph: &Graph, scheme: &[i32]) -> Result<Vec<Vec<u32>>, NotChordal> {
+    independent_set_and_clique_cover(graph, scheme).map(|(_, clique_cover)| clique_cover)
+}
+
+// G is trivially perfect, or (C4, P4)-free, iff the closed neighbourhoods of the two
+// ends of every edge are nested. With the vertices sorted by decreasing degree, it's enough
+// to check that N[v] ⊆ N[p] for the last neighbour p of v that comes before it: every earlier
+// neighbour w of v is then in N[p] and before p, so by induction N[v] ⊆ N[p] ⊆ N[w]
+// When N[v] ⊄ N[p], a neighbour x of v misses p, and since N[p] is no smaller, a neighbour y
+// of p misses v, making x - v - p - y a P4, or a C4 if x and y are adjacent
+// This is O(n + m log n)
+pub fn is_trivially_perfect(graph: &Graph) -> Result<(), ForbiddenSubgraph> {
+    let n = graph.node_count();
+    let degree = |v: u32| graph.neighbors_slice(v).len();
+
+    // counting sort, by decreasing degree
+    let mut by_degree = vec![Vec::new(); n];
+    for v in 0..n as u32 {
+        by_degree[degree(v)].push(v);
+    }
+    let order: Vec<u32> = by_degree.into_iter().rev().flatten().collect();
+    let mut position = vec![0; n];
+    for (i, &v) in order.iter().enumerate() {
+        position[v as usize] = i;
+    }
+
+    for &v in &order {
+        let parent = graph
+            .neighbors_slice(v)
+            .iter()
+            .cloned()
+            .filter(|&w| position[w as usize] < position[v as usize])
+            .max_by_key(|&w| position[w as usize]);
+
+        let p = match parent {
+            Some(p) => p,
+            None => continue,
+        };
+        let missing = |a: u32, b: u32| {
+            graph
+                .neighbors_slice(a)
+                .iter()
+                .cloned()
+                .find(|&w| w != b && !graph.contains_edge(b, w))
+        };
+
+        if let Some(x) = missing(v, p) {
+            let y = missing(p, v).unwrap();
+
+            return Err(if graph.contains_edge(x, y) {
+                ForbiddenSubgraph::C4([x, v, p, y])
+            } else {
+                ForbiddenSubgraph::P4([x, v, p, y])
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Block graphs are the chordal graphs with no diamond, which are those whose minimal
+// separators are all single vertices. Every separator of a clique tree is a minimal separator,
+// and one with two vertices a and b, between the cliques C and C', gives a diamond with
+// any x of C - C' and y of C' - C, which the separator keeps apart. This is O(n + m)
+pub fn is_block_graph(graph: &Graph) -> Result<(), ForbiddenSubgraph> {
+    let tree = CliqueTree::new(graph)?;
+
+    for (child, parent, separator) in tree.separators() {
+        if separator.len() < 2 {
+            continue;
+        }
+
+        let outside = |clique: &[u32]| {
+            clique
+                .iter()
+                .cloned()
+                .find(|v| separator.binary_search(v).is_err())
+                .unwrap()
+        };
+        let (x, y) = (outside(tree.clique(child)), outside(tree.clique(parent)));
+
+        return Err(ForbiddenSubgraph::Diamond([
+            x,
+            separator[0],
+            y,
+            separator[1],
+        ]));
+    }
+
+    Ok(())
+}
+
+// Ptolemaic graphs are the chordal graphs with no gem. A gem is a P4 together with a vertex
+// adjacent to all of it, so G has none iff every neighbourhood induces a cograph
+// We build the cotree of each neighbourhood, which is O(n + m) for each one, and so O(nm)
+pub fn is_ptolemaic(graph: &Graph) -> Result<(), ForbiddenSubgraph> {
+    is_chordal_with_certificate(graph)?;
+
+    for v in 0..graph.node_count() as u32 {
+        let neighbours = graph.neighbors_slice(v);
+
+        if let Err(ForbiddenSubgraph::P4(path)) = Cotree::new(&induced_subgraph(graph, neighbours))
+        {
+            let [a, b, c, d] = path;
+
+            return Err(ForbiddenSubgraph::Gem([
+                neighbours[a as usize],
+                neighbours[b as usize],
+                neighbours[c as usize],
+                neighbours[d as usize],
+                v,
+            ]));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{graph_from_edges, graph_from_reader};
+    use crate::serial::lex_bfs;
+    use crate::test_util::assert_induces;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    #[test]
+    fn gem_graph_cliques() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(b, d, ());
+        graph.add_edge(b, e, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, e, ());
+        graph.add_edge(e, a, ());
+
+        let scheme = lex_bfs(&graph);
+        let mut cliques = maximal_cliques(&graph, &scheme).unwrap();
+
+        println!("{:?}", cliques);
+
+        cliques.sort();
+        assert_eq!(cliques, vec![vec![0, 1, 4], vec![1, 2, 3], vec![1, 3, 4]]);
+        assert_eq!(clique_number(&graph), Ok(3));
+    }
+
+    #[test]
+    fn cliques_from_bad_scheme() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+
+        let mut cliques = maximal_cliques(&graph, &[1, 0, 2]).unwrap();
+        cliques.sort();
+
+        assert_eq!(cliques, vec![vec![0, 1], vec![1, 2]]);
+    }
+
+    #[test]
+    fn not_chordal_cliques() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, a, ());
+
+        let scheme = lex_bfs(&graph);
+        let err = maximal_cliques(&graph, &scheme).unwrap_err();
+
+        assert_eq!(err.hole.len(), 4);
+        assert!(clique_number(&graph).is_err());
+    }
+
+    #[test]
+    fn from_file_cliques() {
+        let file = File::open("k10.txt").unwrap();
+
+        let graph = graph_from_reader(BufReader::new(file)).unwrap();
+
+        let cliques = maximal_cliques(&graph, &lex_bfs(&graph)).unwrap();
+
+        assert_eq!(cliques, vec![(0..10).collect::<Vec<_>>()]);
+        assert_eq!(clique_number(&graph), Ok(10));
+    }
+
+    #[test]
+    fn long_chordal_coloring() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+        let f = graph.add_node(());
+        let g = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+
+        graph.add_edge(g, f, ());
+        graph.add_edge(f, e, ());
+
+        graph.add_edge(g, b, ());
+        graph.add_edge(b, f, ());
+        graph.add_edge(f, c, ());
+        graph.add_edge(c, e, ());
+
+        graph.add_edge(a, g, ());
+        graph.add_edge(e, d, ());
+
+        let color = color_chordal(&graph).unwrap();
+
+        println!("{:?}", color);
+
+        for v in 0..graph.node_count() as u32 {
+            for &w in graph.neighbors_slice(v) {
+                assert_ne!(color[v as usize], color[w as usize]);
+            }
+        }
+
+        assert_eq!(color.iter().max(), Some(&2));
+        assert_eq!(chromatic_number(&graph), Ok(3));
+    }
+
+    #[test]
+    fn not_chordal_coloring() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, a, ());
+
+        let err = color_chordal(&graph).unwrap_err();
+
+        assert_eq!(err.hole.len(), 4);
+        assert!(chromatic_number(&graph).is_err());
+    }
+
+    #[test]
+    fn from_file_coloring() {
+        let file = File::open("k10.txt").unwrap();
+
+        let graph = graph_from_reader(BufReader::new(file)).unwrap();
+
+        assert_eq!(chromatic_number(&graph), Ok(10));
+    }
+
+    #[test]
+    fn gem_graph_independent_set() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(b, d, ());
+        graph.add_edge(b, e, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, e, ());
+        graph.add_edge(e, a, ());
+
+        let scheme = lex_bfs(&graph);
+        let (independent_set, clique_cover) =
+            independent_set_and_clique_cover(&graph, &scheme).unwrap();
+
+        println!("{:?} {:?}", independent_set, clique_cover);
+
+        assert_eq!(independent_set.len(), 2);
+        for &v in &independent_set {
+            for &w in &independent_set {
+                assert!(!graph.contains_edge(v, w));
+            }
+        }
+
+        let mut covered: Vec<u32> = clique_cover.iter().flatten().cloned().collect();
+        covered.sort();
+        assert_eq!(covered, vec![0, 1, 2, 3, 4]);
+        for clique in &clique_cover {
+            for &v in clique {
+                for &w in clique.iter().filter(|&&w| w != v) {
+                    assert!(graph.contains_edge(v, w));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn path_independent_set() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, e, ());
+
+        let scheme = lex_bfs(&graph);
+
+        assert_eq!(maximum_independent_set(&graph, &scheme).unwrap().len(), 3);
+        assert_eq!(minimum_clique_cover(&graph, &scheme).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn independent_set_certificates() {
+        // a path 0 - 1 - 2 - 3 - 4
+        let graph = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let check = |set: &[u32], cover: &[Vec<u32>]| {
+            is_independent_set_and_clique_cover(&graph, set, cover)
+        };
+
+        assert!(check(&[0, 2, 4], &[vec![0, 1], vec![2, 3], vec![4]]));
+
+        // adjacent vertices in the set
+        assert!(!check(&[0, 1, 4], &[vec![0], vec![1, 2], vec![3, 4]]));
+        // a cover member that isn't a clique
+        assert!(!check(&[0, 2, 4], &[vec![0, 1], vec![2], vec![3, 4, 1]]));
+        // a vertex covered twice, or not at all
+        assert!(!check(&[0, 2, 4], &[vec![0, 1], vec![1, 2], vec![3, 4]]));
+        assert!(!check(&[0, 2, 4], &[vec![0, 1], vec![2, 3], vec![3]]));
+        // sizes that don't match prove nothing
+        assert!(!check(&[0, 2], &[vec![0, 1], vec![2, 3], vec![4]]));
+        // a vertex counted twice in the set
+        assert!(!check(&[0, 0, 4], &[vec![0, 1], vec![2, 3], vec![4]]));
+
+        let edge = graph_from_edges(2, vec![(0, 1)]);
+        assert!(!is_independent_set_and_clique_cover(
+            &edge,
+            &[0, 0],
+            &[vec![0], vec![1]]
+        ));
+    }
+
+    #[test]
+    fn trivially_perfect() {
+        // a star with a triangle hanging from one of its leaves, all joined to a universal vertex
+        let graph = graph_from_edges(
+            7,
+            vec![
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (0, 4),
+                (0, 5),
+                (0, 6),
+                (1, 2),
+                (1, 3),
+                (1, 4),
+                (4, 5),
+                (4, 6),
+                (5, 6),
+                (1, 5),
+                (1, 6),
+            ],
+        );
+
+        assert_eq!(is_trivially_perfect(&graph), Ok(()));
+    }
+
+    #[test]
+    fn not_trivially_perfect() {
+        let path = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+        match is_trivially_perfect(&path) {
+            Err(ForbiddenSubgraph::P4(p)) => assert_induces(&path, &p, &[(0, 1), (1, 2), (2, 3)]),
+            other => panic!("expected a P4, got {:?}", other),
+        }
+
+        let cycle = graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3), (3, 0)]);
+        match is_trivially_perfect(&cycle) {
+            Err(ForbiddenSubgraph::C4(c)) => {
+                assert_induces(&cycle, &c, &[(0, 1), (1, 2), (2, 3), (3, 0)])
+            }
+            other => panic!("expected a C4, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn block_graphs() {
+        // two triangles and an edge, glued at cut vertices
+        let graph = graph_from_edges(
+            6,
+            vec![(0, 1), (1, 2), (0, 2), (2, 3), (3, 4), (4, 5), (3, 5)],
+        );
+        assert_eq!(is_block_graph(&graph), Ok(()));
+
+        let diamond = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 0), (1, 3), (3, 4)]);
+        match is_block_graph(&diamond) {
+            Err(ForbiddenSubgraph::Diamond(d)) => {
+                println!("{:?}", d);
+                assert_induces(&diamond, &d, &[(0, 1), (1, 2), (2, 3), (3, 0), (1, 3)])
+            }
+            other => panic!("expected a diamond, got {:?}", other),
+        }
+
+        let cycle = graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3), (3, 0)]);
+        match is_block_graph(&cycle) {
+            Err(ForbiddenSubgraph::Hole(hole)) => assert_eq!(hole.len(), 4),
+            other => panic!("expected a hole, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ptolemaic() {
+        // a diamond is ptolemaic, while adding a path to it makes a gem
+        let diamond = graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3), (3, 0), (1, 3)]);
+        assert_eq!(is_ptolemaic(&diamond), Ok(()));
+
+        let gem = graph_from_edges(
+            5,
+            vec![(0, 1), (1, 2), (2, 3), (4, 0), (4, 1), (4, 2), (4, 3)],
+        );
+        match is_ptolemaic(&gem) {
+            Err(ForbiddenSubgraph::Gem(g)) => assert_induces(
+                &gem,
+                &g,
+                &[(0, 1), (1, 2), (2, 3), (4, 0), (4, 1), (4, 2), (4, 3)],
+            ),
+            other => panic!("expected a gem, got {:?}", other),
+        }
+
+        let cycle = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        assert!(is_ptolemaic(&cycle).is_err());
+    }
+}
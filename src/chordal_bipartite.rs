@@ -0,0 +1,275 @@
+use std::collections::VecDeque;
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+type Graph = Csr<(), (), Undirected>;
+
+// Two colouring of G by breadth first search, with side[v] telling which side v is on
+// None if G has an odd cycle. This is O(n + m)
+pub fn bipartition(graph: &Graph) -> Option<Vec<bool>> {
+    let n = graph.node_count();
+    let mut side = vec![None; n];
+    let mut queue = VecDeque::new();
+
+    for root in 0..n as u32 {
+        if side[root as usize].is_some() {
+            continue;
+        }
+        side[root as usize] = Some(false);
+        queue.push_back(root);
+
+        while let Some(v) = queue.pop_front() {
+            let colour = side[v as usize].unwrap();
+
+            for &w in graph.neighbors_slice(v) {
+                match side[w as usize] {
+                    None => {
+                        side[w as usize] = Some(!colour);
+                        queue.push_back(w);
+                    }
+                    Some(other) if other == colour => return None,
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    Some(side.into_iter().map(Option::unwrap).collect())
+}
+
+// The biadjacency matrix of a bipartite graph, with one row for each vertex on the false side
+// and one column for each on the true side, both in increasing order. Returns the vertices
+// behind the rows and columns, and the matrix
+pub fn biadjacency_matrix(graph: &Graph, side: &[bool]) -> (Vec<u32>, Vec<u32>, Vec<Vec<bool>>) {
+    let (columns, rows): (Vec<u32>, Vec<u32>) =
+        (0..graph.node_count() as u32).partition(|&v| side[v as usize]);
+
+    let matrix = rows
+        .iter()
+        .map(|&r| columns.iter().map(|&c| graph.contains_edge(r, c)).collect())
+        .collect();
+
+    (rows, columns, matrix)
+}
+
+// Doubly lexical ordering of a 0/1 matrix: an order of the rows and one of the columns under
+// which the rows, and the columns, read as non-increasing binary strings, the first row and
+// column being the most significant. Lubiw shows that one always exists
+// None if the rows don't all have the same length
+pub fn matrix_doubly_lexical_ordering(matrix: &[Vec<bool>]) -> Option<(Vec<usize>, Vec<usize>)> {
+    let p = matrix.len();
+    let q = matrix.first().map_or(0, |row| row.len());
+    if matrix.iter().any(|row| row.len() != q) {
+        return None;
+    }
+
+    if q > p {
+        let transposed: Vec<Vec<bool>> = (0..q)
+            .map(|c| matrix.iter().map(|row| row[c]).collect())
+            .collect();
+        let (columns, rows) = column_by_column_ordering(&transposed, p);
+        return Some((rows, columns));
+    }
+
+    Some(column_by_column_ordering(matrix, q))
+}
+
+// Places the columns one at a time, refining the ordered partition of the rows by each of them,
+// its ones first, so that the rows of a class agree on every column placed so far. The next
+// column is the one left with the most ones in the first class, ties going to the second
+// class, and so on. The classes then end up in order, and each column is no smaller than the
+// next: on the first class where they differ, it has at least as many ones, and its ones come
+// first in that class, which it split before the next column could
+// Each column placed costs O(pq), so with the matrix transposed to make q <= p, this is
+// O(pq min(p, q))
+fn column_by_column_ordering(matrix: &[Vec<bool>], q: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut classes = vec![(0..matrix.len()).collect::<Vec<_>>()];
+    let mut left: Vec<usize> = (0..q).collect();
+    let mut columns = Vec::with_capacity(q);
+
+    while !left.is_empty() {
+        let counts = |c: usize| -> Vec<usize> {
+            classes
+                .iter()
+                .map(|class| class.iter().filter(|&&r| matrix[r][c]).count())
+                .collect()
+        };
+        let (i, _) = left
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, &c)| counts(c))
+            .unwrap();
+        let c = left.remove(i);
+        columns.push(c);
+
+        classes = classes
+            .into_iter()
+            .flat_map(|class| {
+                let (ones, zeros): (Vec<usize>, Vec<usize>) =
+                    class.into_iter().partition(|&r| matrix[r][c]);
+                vec![ones, zeros]
+            })
+            .filter(|class| !class.is_empty())
+            .collect();
+    }
+
+    (classes.concat(), columns)
+}
+
+// Lubiw's orders go the other way from ours, with the most significant row and column last,
+// and a Γ is a submatrix, on rows i < i' and columns j < j', where (i, j), (i, j') and (i', j)
+// are ones, but (i', j') isn't. We check for one with our orders read backwards
+// It's enough to look, for each one, at the next one to its right and the next one below it.
+// For a Γ with j' and i' as close as possible to (i, j), those are (i, j') and (i', j),
+// since anything closer would give a Γ with one of them. This is O(pq)
+pub fn is_gamma_free(matrix: &[Vec<bool>], rows: &[usize], columns: &[usize]) -> bool {
+    let (p, q) = (rows.len(), columns.len());
+    let one = |i: usize, j: usize| matrix[rows[p - 1 - i]][columns[q - 1 - j]];
+
+    // the position of the next one to the right of each entry, and below it
+    let mut right = vec![vec![None; q]; p];
+    let mut below = vec![vec![None; q]; p];
+    for i in (0..p).rev() {
+        for j in (0..q).rev() {
+            if j + 1 < q {
+                right[i][j] = if one(i, j + 1) {
+                    Some(j + 1)
+                } else {
+                    right[i][j + 1]
+                };
+            }
+            if i + 1 < p {
+                below[i][j] = if one(i + 1, j) {
+                    Some(i + 1)
+                } else {
+                    below[i + 1][j]
+                };
+            }
+        }
+    }
+
+    (0..p).all(|i| {
+        (0..q).all(|j| match (one(i, j), right[i][j], below[i][j]) {
+            (true, Some(next_column), Some(next_row)) => one(next_row, next_column),
+            _ => true,
+        })
+    })
+}
+
+// Lubiw: a matrix is totally balanced, with no cycle submatrix of length at least 3,
+// iff any doubly lexical ordering of it has no Γ
+// Rows of different lengths don't make a matrix, so those give false
+pub fn is_totally_balanced(matrix: &[Vec<bool>]) -> bool {
+    match matrix_doubly_lexical_ordering(matrix) {
+        Some((rows, columns)) => is_gamma_free(matrix, &rows, &columns),
+        None => false,
+    }
+}
+
+// A bipartite graph is chordal bipartite when every cycle of length at least 6 has a chord,
+// which happens iff its biadjacency matrix is totally balanced (Golumbic and Goss)
+pub fn is_chordal_bipartite(graph: &Graph) -> bool {
+    match bipartition(graph) {
+        Some(side) => is_totally_balanced(&biadjacency_matrix(graph, &side).2),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{graph_from_edges, matrix_from_reader};
+
+    fn cycle(n: u32) -> Graph {
+        graph_from_edges(n as usize, (0..n).map(|v| (v, (v + 1) % n)))
+    }
+
+    #[test]
+    fn bipartitions() {
+        let side = bipartition(&cycle(6)).unwrap();
+        assert!((0..6).all(|v| side[v] != side[(v + 1) % 6]));
+
+        assert!(bipartition(&cycle(5)).is_none());
+    }
+
+    #[test]
+    fn doubly_lexical() {
+        let matrix = vec![
+            vec![false, true, false, true],
+            vec![true, true, false, false],
+            vec![false, true, true, true],
+        ];
+        let transposed: Vec<Vec<bool>> = (0..4)
+            .map(|c| matrix.iter().map(|row| row[c]).collect())
+            .collect();
+
+        // wider than tall, and taller than wide
+        for matrix in &[matrix, transposed] {
+            let (rows, columns) = matrix_doubly_lexical_ordering(matrix).unwrap();
+
+            println!("{:?} {:?}", rows, columns);
+
+            let row = |r: usize| columns.iter().map(|&c| matrix[r][c]).collect::<Vec<_>>();
+            let column = |c: usize| rows.iter().map(|&r| matrix[r][c]).collect::<Vec<_>>();
+            assert!(rows.windows(2).all(|p| row(p[0]) >= row(p[1])));
+            assert!(columns.windows(2).all(|p| column(p[0]) >= column(p[1])));
+            assert!(is_gamma_free(matrix, &rows, &columns));
+        }
+    }
+
+    #[test]
+    fn ragged_matrix() {
+        let matrix = vec![vec![true], vec![true, true]];
+
+        assert_eq!(matrix_doubly_lexical_ordering(&matrix), None);
+        assert!(!is_totally_balanced(&matrix));
+    }
+
+    #[test]
+    fn cycles() {
+        assert!(is_chordal_bipartite(&cycle(4)));
+        assert!(!is_chordal_bipartite(&cycle(6)));
+        assert!(!is_chordal_bipartite(&cycle(8)));
+        // bipartite, but the complement of a perfect matching on 3 + 3 vertices is a C6
+        assert!(!is_chordal_bipartite(&graph_from_edges(
+            6,
+            vec![(0, 4), (0, 5), (1, 3), (1, 5), (2, 3), (2, 4)]
+        )));
+        // not bipartite at all
+        assert!(!is_chordal_bipartite(&cycle(3)));
+    }
+
+    #[test]
+    fn chorded_cycle() {
+        // a C8 with the chord 0 - 5 still has the C6 0, ..., 5, until 1 - 4 splits it
+        let mut edges: Vec<(u32, u32)> = (0..8).map(|v| (v, (v + 1) % 8)).collect();
+        edges.push((0, 5));
+        assert!(!is_chordal_bipartite(&graph_from_edges(8, edges.clone())));
+
+        edges.push((1, 4));
+        assert!(is_chordal_bipartite(&graph_from_edges(8, edges)));
+    }
+
+    #[test]
+    fn rectangular_matrix() {
+        // the biadjacency matrices of two C6s, then of a tree
+        let input = "1 1 0\n0 1 1\n1 0 1\n";
+        let matrix = matrix_from_reader(input.as_bytes()).unwrap();
+
+        assert_eq!((matrix.len(), matrix[0].len()), (3, 3));
+        assert!(!is_totally_balanced(&matrix));
+
+        let input = "1 1 0 0\n0 1 1 1\n1 0 1 0\n";
+        assert!(!is_totally_balanced(
+            &matrix_from_reader(input.as_bytes()).unwrap()
+        ));
+
+        let input = "1 1 0 0 1\n1 1 1 0 0\n";
+        let matrix = matrix_from_reader(input.as_bytes()).unwrap();
+        assert_eq!((matrix.len(), matrix[0].len()), (2, 5));
+        assert!(is_totally_balanced(&matrix));
+
+        assert!(matrix_from_reader("1 0\n1\n".as_bytes()).is_err());
+    }
+}
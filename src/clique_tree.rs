@@ -0,0 +1,244 @@
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::chordal::{absorbed_by, later_neighbours, perfect_scheme, sort_vertex_lists};
+use crate::common::{scheme_positions, NotChordal};
+use crate::serial::lex_bfs;
+
+type Graph = Csr<(), (), Undirected>;
+
+// A clique tree of a chordal graph: its nodes are the maximal cliques, and for every vertex,
+// the cliques containing it induce a subtree (the running intersection property)
+// The label of each edge, the intersection of its two cliques, is a minimal separator
+// Disconnected graphs get a forest, with one root per component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliqueTree {
+    cliques: Vec<Vec<u32>>,
+    parents: Vec<Option<usize>>,
+    // the intersection of each clique with its parent, empty for roots
+    separators: Vec<Vec<u32>>,
+}
+
+impl CliqueTree {
+    pub fn new(graph: &Graph) -> Result<CliqueTree, NotChordal> {
+        CliqueTree::from_scheme(graph, &lex_bfs(graph))
+    }
+
+    // Every maximal clique is some vertex v of the PES together with its later neighbours
+    // Walking up from v through parents, we find the first vertex x whose parent p lies
+    // in another clique: the one holding p together with its later neighbours, which contains
+    // all the later neighbours of x. Those are the separator between both cliques
+    // Every vertex is walked over by exactly one clique, so this is O(n + m)
+    pub fn from_scheme(graph: &Graph, scheme: &[i32]) -> Result<CliqueTree, NotChordal> {
+        let scheme = perfect_scheme(graph, scheme)?;
+        let later = later_neighbours(graph, &scheme);
+        let position = scheme_positions(&scheme);
+        let absorbed_by = absorbed_by(&later, &position);
+
+        // index of the maximal clique containing each vertex and its later neighbours
+        // children come before their parents, so the absorbing clique is already known
+        let mut containing = vec![0; scheme.len()];
+        let mut tree = CliqueTree {
+            cliques: Vec::new(),
+            parents: Vec::new(),
+            separators: Vec::new(),
+        };
+
+        for i in 0..scheme.len() {
+            match absorbed_by[i] {
+                Some(child) => containing[i] = containing[child],
+                None => {
+                    containing[i] = tree.cliques.len();
+
+                    let mut clique = later[i].clone();
+                    clique.push(scheme[i] as u32);
+                    tree.cliques.push(clique);
+                }
+            }
+        }
+
+        for i in (0..scheme.len()).filter(|&i| absorbed_by[i].is_none()) {
+            let mut x = i;
+            let mut parent = None;
+
+            while let Some(&p) = later[x].first() {
+                let p = position[p as usize];
+
+                if containing[p] != containing[i] {
+                    parent = Some(containing[p]);
+                    break;
+                }
+
+                x = p;
+            }
+
+            let separator = if parent.is_some() {
+                later[x].clone()
+            } else {
+                Vec::new()
+            };
+
+            tree.parents.push(parent);
+            tree.separators.push(separator);
+        }
+
+        tree.cliques = sort_vertex_lists(scheme.len(), tree.cliques);
+        tree.separators = sort_vertex_lists(scheme.len(), tree.separators);
+
+        Ok(tree)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cliques.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cliques.is_empty()
+    }
+
+    pub fn clique(&self, index: usize) -> &[u32] {
+        &self.cliques[index]
+    }
+
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        self.parents[index]
+    }
+
+    // The separator between a clique and its parent, empty for roots
+    pub fn separator(&self, index: usize) -> &[u32] {
+        &self.separators[index]
+    }
+
+    // Maximal cliques, each one sorted
+    pub fn cliques(&self) -> impl Iterator<Item = &[u32]> {
+        self.cliques.iter().map(|clique| clique.as_slice())
+    }
+
+    pub fn parents(&self) -> impl Iterator<Item = Option<usize>> + '_ {
+        self.parents.iter().cloned()
+    }
+
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        self.parents()
+            .enumerate()
+            .filter(|&(_, parent)| parent.is_none())
+            .map(|(index, _)| index)
+    }
+
+    // Tree edges as (clique, parent, separator)
+    pub fn separators(&self) -> impl Iterator<Item = (usize, usize, &[u32])> {
+        self.parents
+            .iter()
+            .zip(&self.separators)
+            .enumerate()
+            .filter_map(|(index, (parent, separator))| {
+                parent.map(|parent| (index, parent, separator.as_slice()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every vertex must induce a subtree, i.e., its cliques must have one less edge among them
+    fn assert_running_intersection(graph: &Graph, tree: &CliqueTree) {
+        for v in 0..graph.node_count() as u32 {
+            let nodes = tree.cliques().filter(|clique| clique.contains(&v)).count();
+            let edges = tree
+                .separators()
+                .filter(|(_, _, separator)| separator.contains(&v))
+                .count();
+
+            assert!(nodes > 0);
+            assert_eq!(nodes, edges + 1);
+        }
+    }
+
+    #[test]
+    fn long_chordal_clique_tree() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+        let f = graph.add_node(());
+        let g = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+
+        graph.add_edge(g, f, ());
+        graph.add_edge(f, e, ());
+
+        graph.add_edge(g, b, ());
+        graph.add_edge(b, f, ());
+        graph.add_edge(f, c, ());
+        graph.add_edge(c, e, ());
+
+        graph.add_edge(a, g, ());
+        graph.add_edge(e, d, ());
+
+        let tree = CliqueTree::new(&graph).unwrap();
+
+        println!("{:?}", tree);
+
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.roots().count(), 1);
+        assert_running_intersection(&graph, &tree);
+
+        for (child, parent, separator) in tree.separators() {
+            let mut intersection: Vec<u32> = tree
+                .clique(child)
+                .iter()
+                .cloned()
+                .filter(|v| tree.clique(parent).contains(v))
+                .collect();
+            intersection.sort();
+
+            assert_eq!(separator, intersection.as_slice());
+            assert_eq!(separator.len(), 2);
+        }
+    }
+
+    #[test]
+    fn forest_clique_tree() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(d, e, ());
+
+        let tree = CliqueTree::new(&graph).unwrap();
+
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.roots().count(), 2);
+        assert_running_intersection(&graph, &tree);
+    }
+
+    #[test]
+    fn not_chordal_clique_tree() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, a, ());
+
+        assert!(CliqueTree::new(&graph).is_err());
+    }
+}
@@ -0,0 +1,808 @@
+use std::cmp::Ordering;
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::common::{graph_from_edges, scheme_positions, ForbiddenSubgraph};
+use crate::serial::{complement_lex_bfs, lex_bfs};
+
+type Graph = Csr<(), (), Undirected>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CotreeNode {
+    Leaf(u32),
+    // the disjoint union of the children
+    Parallel(Vec<usize>),
+    // the join of the children, every vertex of one adjacent to every vertex of the others
+    Series(Vec<usize>),
+}
+
+// The cotree of a cograph: its leaves are the vertices, and two of them are adjacent iff their
+// lowest common ancestor is a series node. Parallel and series nodes alternate along every
+// path, and each has at least two children, which makes the cotree unique
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cotree {
+    nodes: Vec<CotreeNode>,
+    root: Option<usize>,
+}
+
+// Marks a vertex as not seen yet, or a node as not there
+const NONE: usize = usize::MAX;
+
+// A node of the cotree still to be built, with its vertices in the order of both sweeps,
+// and the node it hangs from
+struct Pending {
+    sigma: Vec<u32>,
+    tau: Vec<u32>,
+    parent: Option<usize>,
+}
+
+impl Cotree {
+    // A LexBFS recognition after Bretscher, Corneil, Habib and Paul: a sweep σ of the graph,
+    // then a sweep τ of its complement guided by σ, here starting where σ did and breaking ties
+    // in favour of the vertex σ visited first. Rather than testing the sweeps themselves, we
+    // read the cotree off them top-down, and then check it against the graph
+    // Take a node X, and x the vertex both sweeps visit first in X. The nodes on the way down
+    // from X to x split the rest of X into levels, the branches hanging off each of them, and
+    // the levels off parallel nodes are the non-neighbours of x. σ visits them after the
+    // neighbours of x, level by level from the nearest to x, and each branch in one go. τ does
+    // the same with the neighbours of x, in the complement
+    // A vertex with no more neighbours before it in σ than the first vertex of its level starts
+    // a new branch, and one with fewer starts the next level, and likewise in τ with
+    // non-neighbours. Nodes alternate, so the levels interleave, and we build the nodes down to
+    // x before doing the same in every branch
+    // A node costs its size plus d(x). Every other node above a vertex v is a series node,
+    // which joins v to a vertex in another of its branches, so v lies below at most 2d(v) + 1
+    // nodes, and this is O(n + m)
+    // Those claims only hold for cographs, and checking the cotree is what makes this a
+    // recognition. If that fails, we look for an induced P4 through each vertex in turn, which
+    // is O(n(n + m)) at worst
+    pub fn new(graph: &Graph) -> Result<Cotree, ForbiddenSubgraph> {
+        match Cotree::from_sweeps(graph) {
+            Some(cotree) if cotree.describes(graph) => Ok(cotree),
+            _ => {
+                let path = (0..graph.node_count() as u32).find_map(|x| p4_through(graph, x));
+
+                Err(ForbiddenSubgraph::P4(
+                    path.expect("a graph that isn't a cograph has an induced P4"),
+                ))
+            }
+        }
+    }
+
+    // The cotree as read off the sweeps, which is wrong or missing if the graph isn't a cograph
+    fn from_sweeps(graph: &Graph) -> Option<Cotree> {
+        let n = graph.node_count();
+        let mut cotree = Cotree {
+            nodes: Vec::new(),
+            root: None,
+        };
+        if n == 0 {
+            return Some(cotree);
+        }
+
+        let sigma_scheme = lex_bfs(graph);
+        let order: Vec<i32> = sigma_scheme.iter().rev().cloned().collect();
+        let tau_scheme = complement_lex_bfs(graph, &order);
+
+        // how many neighbours of each vertex come before it in σ, and non-neighbours in τ
+        let sigma_earlier = earlier_neighbours(graph, &sigma_scheme);
+        let tau_position = scheme_positions(&tau_scheme);
+        let tau_earlier: Vec<usize> = earlier_neighbours(graph, &tau_scheme)
+            .into_iter()
+            .enumerate()
+            .map(|(v, earlier)| n - 1 - tau_position[v] - earlier)
+            .collect();
+
+        // per vertex, the turn of the last node it was in, the turn in which it was found to
+        // be a neighbour of the first vertex of its node, and its branch below that node
+        let mut turn_of = vec![NONE; n];
+        let mut neighbour_in = vec![NONE; n];
+        let mut branch_of = vec![0; n];
+        let mut pending = vec![Pending {
+            sigma: order.iter().map(|&v| v as u32).collect(),
+            tau: tau_scheme.iter().rev().map(|&v| v as u32).collect(),
+            parent: None,
+        }];
+
+        let mut turn = 0;
+        while let Some(Pending { sigma, tau, parent }) = pending.pop() {
+            let x = sigma[0];
+            if tau[0] != x {
+                return None;
+            }
+            if sigma.len() == 1 {
+                cotree.add(CotreeNode::Leaf(x), parent);
+                continue;
+            }
+
+            turn += 1;
+            for &v in &sigma {
+                turn_of[v as usize] = turn;
+            }
+            for &w in graph.neighbors_slice(x) {
+                if turn_of[w as usize] == turn {
+                    neighbour_in[w as usize] = turn;
+                }
+            }
+            let adjacent = |v: &u32| neighbour_in[*v as usize] == turn;
+
+            let mut branches = 0;
+            let parallel = levels(
+                sigma[1..].iter().filter(|v| !adjacent(v)),
+                &sigma_earlier,
+                &mut branch_of,
+                &mut branches,
+            );
+            let series = levels(
+                tau[1..].iter().filter(|v| adjacent(v)),
+                &tau_earlier,
+                &mut branch_of,
+                &mut branches,
+            );
+
+            // the nearest level hangs off a parallel node iff it sees the nearest series level
+            let mut is_series = match parallel.len().cmp(&series.len()) {
+                Ordering::Less => true,
+                Ordering::Greater => false,
+                Ordering::Equal => {
+                    let u = sigma[1..].iter().find(|v| !adjacent(v));
+                    let s = tau[1..].iter().find(|v| adjacent(v));
+                    match (u, s) {
+                        (Some(&u), Some(s)) => !graph.neighbors_slice(u).contains(s),
+                        _ => false,
+                    }
+                }
+            };
+
+            let (mut parallel, mut series) = (parallel.into_iter(), series.into_iter());
+            let mut spine = Vec::new();
+            while let Some(level) = if is_series {
+                series.next()
+            } else {
+                parallel.next()
+            } {
+                spine.push((is_series, level));
+                is_series = !is_series;
+            }
+            if parallel.next().is_some() || series.next().is_some() {
+                return None;
+            }
+
+            let mut node_of_branch = vec![0; branches];
+            let mut parent = parent;
+            for (is_series, level) in spine.into_iter().rev() {
+                let node = cotree.add(
+                    if is_series {
+                        CotreeNode::Series(Vec::new())
+                    } else {
+                        CotreeNode::Parallel(Vec::new())
+                    },
+                    parent,
+                );
+                for branch in level {
+                    node_of_branch[branch] = node;
+                }
+                parent = Some(node);
+            }
+            cotree.add(CotreeNode::Leaf(x), parent);
+
+            let mut below: Vec<Pending> = node_of_branch
+                .into_iter()
+                .map(|node| Pending {
+                    sigma: Vec::new(),
+                    tau: Vec::new(),
+                    parent: Some(node),
+                })
+                .collect();
+            for &v in &sigma[1..] {
+                below[branch_of[v as usize]].sigma.push(v);
+            }
+            for &v in &tau[1..] {
+                below[branch_of[v as usize]].tau.push(v);
+            }
+            pending.extend(below);
+        }
+
+        Some(cotree)
+    }
+
+    // Adds a node as the last child of parent, or as the root
+    fn add(&mut self, node: CotreeNode, parent: Option<usize>) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(node);
+
+        match parent.map(|parent| &mut self.nodes[parent]) {
+            Some(CotreeNode::Parallel(children)) | Some(CotreeNode::Series(children)) => {
+                children.push(index)
+            }
+            Some(CotreeNode::Leaf(_)) => unreachable!("leaves have no children"),
+            None => self.root = Some(index),
+        }
+
+        index
+    }
+
+    // Whether this is the cotree of the graph, with nodes alternating and at least two children
+    // Numbering the leaves from left to right, every node spans a range of numbers, and the
+    // neighbours of a leaf after it must be, from the bottom up, what each series node above it
+    // spans past the branch holding it. Each node keeps the nearest of those ranges above it
+    // that isn't empty, so a leaf gets through its ranges in O(1 + d(v)), and sorting the
+    // neighbours by number is a bucket pass, which makes this O(n + m)
+    fn describes(&self, graph: &Graph) -> bool {
+        let n = graph.node_count();
+        let order = self.post_order();
+        let is_series = |node: usize| matches!(self.nodes[node], CotreeNode::Series(_));
+
+        let mut number = vec![NONE; n];
+        let mut leaf = vec![NONE; n];
+        let mut vertex_at = Vec::with_capacity(n);
+        // the last number each node spans
+        let mut last = vec![0; self.nodes.len()];
+
+        for &node in &order {
+            match &self.nodes[node] {
+                CotreeNode::Leaf(v) => {
+                    let v = *v as usize;
+                    if v >= n || number[v] != NONE {
+                        return false;
+                    }
+
+                    number[v] = vertex_at.len();
+                    leaf[v] = node;
+                    last[node] = number[v];
+                    vertex_at.push(v as u32);
+                }
+                CotreeNode::Parallel(children) | CotreeNode::Series(children) => {
+                    let alternates = children.iter().all(|&c| {
+                        matches!(self.nodes[c], CotreeNode::Leaf(_))
+                            || is_series(c) != is_series(node)
+                    });
+                    if children.len() < 2 || !alternates {
+                        return false;
+                    }
+
+                    last[node] = children.iter().map(|&c| last[c]).max().unwrap_or(0);
+                }
+            }
+        }
+        if vertex_at.len() != n {
+            return false;
+        }
+
+        // the nearest non-empty range above each node, with the series node it comes from
+        let mut above: Vec<Option<(usize, usize, usize)>> = vec![None; self.nodes.len()];
+        for &node in order.iter().rev() {
+            if let CotreeNode::Parallel(children) | CotreeNode::Series(children) = &self.nodes[node]
+            {
+                for &c in children {
+                    above[c] = if is_series(node) && last[c] < last[node] {
+                        Some((last[c] + 1, last[node], node))
+                    } else {
+                        above[node]
+                    };
+                }
+            }
+        }
+
+        let mut later = vec![Vec::new(); n];
+        for (i, &v) in vertex_at.iter().enumerate() {
+            for &w in graph.neighbors_slice(v) {
+                if number[w as usize] < i {
+                    later[w as usize].push(i);
+                }
+            }
+        }
+
+        // the numbers are increasing, so matching both ends of a range matches all of it
+        (0..n).all(|v| {
+            let mut next = 0;
+            let mut range = above[leaf[v]];
+
+            while let Some((start, end, node)) = range {
+                let end_at = next + end - start;
+                if later[v].get(next) != Some(&start) || later[v].get(end_at) != Some(&end) {
+                    return false;
+                }
+
+                next = end_at + 1;
+                range = above[node];
+            }
+
+            next == later[v].len()
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    pub fn node(&self, index: usize) -> &CotreeNode {
+        &self.nodes[index]
+    }
+
+    // Every node after all of its children
+    fn post_order(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            if let CotreeNode::Parallel(children) | CotreeNode::Series(children) = &self.nodes[node]
+            {
+                stack.extend(children);
+            }
+        }
+
+        order.reverse();
+        order
+    }
+
+    // Evaluates a leaf value bottom-up, combining the children of series and parallel nodes
+    fn fold(
+        &self,
+        parallel: fn(usize, usize) -> usize,
+        series: fn(usize, usize) -> usize,
+    ) -> usize {
+        let mut value = vec![0; self.nodes.len()];
+
+        for node in self.post_order() {
+            value[node] = match &self.nodes[node] {
+                CotreeNode::Leaf(_) => 1,
+                CotreeNode::Parallel(children) => {
+                    children.iter().map(|&c| value[c]).fold(0, parallel)
+                }
+                CotreeNode::Series(children) => children.iter().map(|&c| value[c]).fold(0, series),
+            };
+        }
+
+        self.root.map_or(0, |root| value[root])
+    }
+
+    // ω(G): the children of a series node are joined, so their cliques add up
+    pub fn clique_number(&self) -> usize {
+        self.fold(usize::max, |a, b| a + b)
+    }
+
+    // α(G), the same as ω in the complement, where series and parallel nodes swap
+    pub fn independence_number(&self) -> usize {
+        self.fold(|a, b| a + b, usize::max)
+    }
+
+    // An optimal colouring: the children of a parallel node can reuse the same colours,
+    // while those of a series node each need their own, so we shift them past each other
+    // Cographs are perfect, so this uses exactly ω(G) colours. This is O(n)
+    pub fn color(&self) -> Vec<u32> {
+        let vertex_count = self
+            .nodes
+            .iter()
+            .filter(|node| matches!(node, CotreeNode::Leaf(_)))
+            .count();
+        let mut color = vec![0; vertex_count];
+        // colour offset of each node, from the top down
+        let mut offset = vec![0; self.nodes.len()];
+        let mut colors_used = vec![0; self.nodes.len()];
+
+        for node in self.post_order() {
+            colors_used[node] = match &self.nodes[node] {
+                CotreeNode::Leaf(_) => 1,
+                CotreeNode::Parallel(children) => {
+                    children.iter().map(|&c| colors_used[c]).max().unwrap_or(0)
+                }
+                CotreeNode::Series(children) => children.iter().map(|&c| colors_used[c]).sum(),
+            };
+        }
+
+        for node in self.post_order().into_iter().rev() {
+            match &self.nodes[node] {
+                CotreeNode::Leaf(v) => color[*v as usize] = offset[node],
+                CotreeNode::Parallel(children) => {
+                    for &c in children {
+                        offset[c] = offset[node];
+                    }
+                }
+                CotreeNode::Series(children) => {
+                    let mut next = offset[node];
+                    for &c in children {
+                        offset[c] = next;
+                        next += colors_used[c];
+                    }
+                }
+            }
+        }
+
+        color
+    }
+
+    // χ(G), which equals ω(G) for cographs
+    pub fn chromatic_number(&self) -> usize {
+        self.clique_number()
+    }
+
+    // The cograph this cotree describes
+    pub fn graph(&self) -> Graph {
+        let mut leaves: Vec<Vec<u32>> = vec![Vec::new(); self.nodes.len()];
+        let mut edges = Vec::new();
+
+        for node in self.post_order() {
+            match &self.nodes[node] {
+                CotreeNode::Leaf(v) => leaves[node].push(*v),
+                CotreeNode::Parallel(children) | CotreeNode::Series(children) => {
+                    for (i, &c) in children.iter().enumerate() {
+                        if let CotreeNode::Series(_) = self.nodes[node] {
+                            for &d in &children[..i] {
+                                for &v in &leaves[c] {
+                                    edges.extend(leaves[d].iter().map(|&w| (v, w)));
+                                }
+                            }
+                        }
+                    }
+
+                    leaves[node] = children
+                        .iter()
+                        .flat_map(|&c| leaves[c].iter().cloned())
+                        .collect();
+                }
+            }
+        }
+
+        let vertex_count = self.root.map_or(0, |root| leaves[root].len());
+        graph_from_edges(vertex_count, edges)
+    }
+}
+
+pub fn is_cograph(graph: &Graph) -> bool {
+    Cotree::new(graph).is_ok()
+}
+
+// For every vertex, how many of its neighbours the search behind scheme visited before it
+fn earlier_neighbours(graph: &Graph, scheme: &[i32]) -> Vec<usize> {
+    let position = scheme_positions(scheme);
+
+    (0..graph.node_count() as u32)
+        .map(|v| {
+            graph
+                .neighbors_slice(v)
+                .iter()
+                .filter(|&&w| position[w as usize] > position[v as usize])
+                .count()
+        })
+        .collect()
+}
+
+// Splits vertices, in the order of a sweep, into levels of branches, given how many
+// neighbours each has before it in that sweep, or non-neighbours for the complement
+// Branches are numbered on from count, and each level lists its own
+fn levels<'a>(
+    vertices: impl Iterator<Item = &'a u32>,
+    earlier: &[usize],
+    branch_of: &mut [usize],
+    count: &mut usize,
+) -> Vec<Vec<usize>> {
+    let mut levels: Vec<Vec<usize>> = Vec::new();
+    // the count of the first vertex of the current level
+    let mut first = None;
+
+    for &v in vertices {
+        let earlier = earlier[v as usize];
+
+        match first.map(|first: usize| earlier.cmp(&first)) {
+            // more than the first vertex, so it follows the one before it in its branch
+            Some(Ordering::Greater) => (),
+            Some(Ordering::Equal) => {
+                levels.last_mut().unwrap().push(*count);
+                *count += 1;
+            }
+            _ => {
+                first = Some(earlier);
+                levels.push(vec![*count]);
+                *count += 1;
+            }
+        }
+
+        branch_of[v as usize] = *count - 1;
+    }
+
+    levels
+}
+
+// An induced P4 through x, if there is one
+// x is at an end of one iff a neighbour of x sees part, but not all, of a component of the
+// non-neighbours of x. The ends and the middle of a P4 swap in the complement, so x is in the
+// middle of one iff a non-neighbour of x sees part of a component of the complement of its
+// neighbourhood. Both are O(n + m)
+fn p4_through(graph: &Graph, x: u32) -> Option<[u32; 4]> {
+    let n = graph.node_count();
+    let mut adjacent = vec![false; n];
+    for &w in graph.neighbors_slice(x) {
+        adjacent[w as usize] = true;
+    }
+    let (near, far): (Vec<u32>, Vec<u32>) = (0..n as u32)
+        .filter(|&v| v != x)
+        .partition(|&v| adjacent[v as usize]);
+
+    // x - a - b - c, with b and c in the same component of the non-neighbours
+    if let Some((a, b, c)) = split_component(graph, &far, &near, false) {
+        return Some([x, a, b, c]);
+    }
+
+    // c - x - a - b, with a and c in the same component of the complement of the neighbourhood
+    split_component(graph, &near, &far, true).map(|(b, c, a)| [c, x, a, b])
+}
+
+// Looks for a vertex p of outside that sees part, but not all, of a component of inside, in
+// the graph or, with complement, in its complement. That component then has an edge q - r
+// with p - q and not p - r, all of the same graph, and we return (p, q, r). This is O(n + m)
+fn split_component(
+    graph: &Graph,
+    inside: &[u32],
+    outside: &[u32],
+    complement: bool,
+) -> Option<(u32, u32, u32)> {
+    let (component, count) = components(graph, inside, complement);
+    let mut size = vec![0; count];
+    for &v in inside {
+        size[component[v as usize]] += 1;
+    }
+
+    // a component p sees part of in one graph, it sees the rest of in the other
+    let mut seen = vec![0; count];
+    let mut touched = Vec::new();
+    for &p in outside {
+        for &q in graph.neighbors_slice(p) {
+            let k = component[q as usize];
+            if k != NONE {
+                if seen[k] == 0 {
+                    touched.push(k);
+                }
+                seen[k] += 1;
+            }
+        }
+
+        let split = touched.iter().cloned().find(|&k| seen[k] < size[k]);
+        for k in touched.drain(..) {
+            seen[k] = 0;
+        }
+
+        if let Some(k) = split {
+            return edge_across(graph, inside, &component, k, p, complement);
+        }
+    }
+
+    None
+}
+
+// The edge q - r inside component k that split_component is after
+// In the complement, a vertex q that p misses is checked against those p sees until one isn't
+// a neighbour, so each check is paid for by an edge, or ends the search
+fn edge_across(
+    graph: &Graph,
+    inside: &[u32],
+    component: &[usize],
+    k: usize,
+    p: u32,
+    complement: bool,
+) -> Option<(u32, u32, u32)> {
+    let n = graph.node_count();
+    let mut sees = vec![false; n];
+    for &w in graph.neighbors_slice(p) {
+        sees[w as usize] = true;
+    }
+    let (hit, missed): (Vec<u32>, Vec<u32>) = inside
+        .iter()
+        .filter(|&&v| component[v as usize] == k)
+        .partition(|&&v| sees[v as usize]);
+
+    if !complement {
+        return hit.iter().find_map(|&q| {
+            graph
+                .neighbors_slice(q)
+                .iter()
+                .find(|&&r| component[r as usize] == k && !sees[r as usize])
+                .map(|&r| (p, q, r))
+        });
+    }
+
+    let mut marked = vec![NONE; n];
+    missed.iter().find_map(|&q| {
+        for &w in graph.neighbors_slice(q) {
+            marked[w as usize] = q as usize;
+        }
+
+        hit.iter()
+            .find(|&&r| marked[r as usize] != q as usize)
+            .map(|&r| (p, q, r))
+    })
+}
+
+// The components of the subgraph induced by vertices, or of its complement, and their count
+// The complement is searched keeping the vertices not reached yet, and reaching those that
+// aren't neighbours of the current one, so every vertex kept is paid for by an edge
+fn components(graph: &Graph, vertices: &[u32], complement: bool) -> (Vec<usize>, usize) {
+    let n = graph.node_count();
+    let mut component = vec![NONE; n];
+    let mut member = vec![false; n];
+    for &v in vertices {
+        member[v as usize] = true;
+    }
+
+    let mut marked = vec![NONE; n];
+    let mut remaining = vertices.to_vec();
+    let mut count = 0;
+    while let Some(start) = remaining.pop() {
+        if component[start as usize] != NONE {
+            continue;
+        }
+
+        component[start as usize] = count;
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            if complement {
+                for &w in graph.neighbors_slice(v) {
+                    marked[w as usize] = v as usize;
+                }
+
+                let (kept, reached): (Vec<u32>, Vec<u32>) = remaining
+                    .drain(..)
+                    .partition(|&w| marked[w as usize] == v as usize);
+                for &w in &reached {
+                    component[w as usize] = count;
+                }
+                stack.extend(reached);
+                remaining = kept;
+            } else {
+                for &w in graph.neighbors_slice(v) {
+                    if member[w as usize] && component[w as usize] == NONE {
+                        component[w as usize] = count;
+                        stack.push(w);
+                    }
+                }
+            }
+        }
+
+        count += 1;
+    }
+
+    (component, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::graph_from_reader;
+    use crate::test_util::{assert_induces, random, same_graph};
+    use std::fs::File;
+    use std::io::BufReader;
+
+    #[test]
+    fn complete_graph() {
+        let file = File::open("k10.txt").unwrap();
+
+        let graph = graph_from_reader(BufReader::new(file)).unwrap();
+        let cotree = Cotree::new(&graph).unwrap();
+
+        match cotree.node(cotree.root().unwrap()) {
+            CotreeNode::Series(children) => assert_eq!(children.len(), 10),
+            other => panic!("expected a series root, got {:?}", other),
+        }
+        assert_eq!(cotree.clique_number(), 10);
+        assert_eq!(cotree.independence_number(), 1);
+        assert!(same_graph(&cotree.graph(), &graph));
+    }
+
+    #[test]
+    fn complete_bipartite_plus_isolated() {
+        // K3,3 on 0..6, with 6 isolated
+        let edges = (0..3).flat_map(|a| (3..6).map(move |b| (a, b)));
+        let graph = graph_from_edges(7, edges);
+        let cotree = Cotree::new(&graph).unwrap();
+
+        println!("{:?}", cotree);
+
+        assert_eq!(cotree.clique_number(), 2);
+        assert_eq!(cotree.independence_number(), 4);
+        assert_eq!(cotree.chromatic_number(), 2);
+
+        let color = cotree.color();
+        assert!((0..3).all(|a| (3..6).all(|b| color[a] != color[b])));
+        assert!(color.iter().all(|&c| c < 2));
+        assert!(same_graph(&cotree.graph(), &graph));
+
+        match cotree.node(cotree.root().unwrap()) {
+            CotreeNode::Parallel(children) => assert_eq!(children.len(), 2),
+            other => panic!("expected a parallel root, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nested_cograph() {
+        // ((0 + 1) * (2 + (3 * 4))) + 5, with + for parallel and * for series
+        let edges = vec![(0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (3, 4)];
+        let graph = graph_from_edges(6, edges);
+        let cotree = Cotree::new(&graph).unwrap();
+
+        assert_eq!(cotree.len(), 11);
+        assert_eq!(cotree.clique_number(), 3);
+        assert_eq!(cotree.independence_number(), 3);
+        assert!(same_graph(&cotree.graph(), &graph));
+
+        let color = cotree.color();
+        for v in 0..6 {
+            for &w in graph.neighbors_slice(v) {
+                assert!(color[v as usize] != color[w as usize]);
+            }
+        }
+    }
+
+    #[test]
+    fn paths_are_not_cographs() {
+        let graph = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+
+        match Cotree::new(&graph) {
+            Err(ForbiddenSubgraph::P4(path)) => {
+                println!("{:?}", path);
+                assert!(path.windows(2).all(|p| graph.contains_edge(p[0], p[1])));
+                assert!(!graph.contains_edge(path[0], path[2]));
+                assert!(!graph.contains_edge(path[0], path[3]));
+                assert!(!graph.contains_edge(path[1], path[3]));
+            }
+            other => panic!("expected a P4, got {:?}", other),
+        }
+        assert!(!is_cograph(&graph));
+    }
+
+    #[test]
+    fn p4_away_from_the_first_vertices() {
+        // 0 and 1 are on no P4, the path 2 - 3 - 4 - 5 is
+        let graph = graph_from_edges(6, vec![(0, 1), (2, 3), (3, 4), (4, 5)]);
+
+        match Cotree::new(&graph) {
+            Err(ForbiddenSubgraph::P4(path)) => {
+                assert_induces(&graph, &path, &[(0, 1), (1, 2), (2, 3)])
+            }
+            other => panic!("expected a P4, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn random_graphs() {
+        // either the cotree describes the graph, or the graph has an induced P4
+        let mut state = 0x2545_f491_4f6c_dd1d;
+
+        for _ in 0..1000 {
+            let n = 1 + random(&mut state, 10);
+            let density = 1 + random(&mut state, 9);
+            let edges: Vec<_> = (0..n as u32)
+                .flat_map(|v| (0..v).map(move |w| (w, v)))
+                .filter(|_| random(&mut state, 10) < density)
+                .collect();
+            let graph = graph_from_edges(n, edges);
+
+            match Cotree::new(&graph) {
+                Ok(cotree) => assert!(same_graph(&cotree.graph(), &graph)),
+                Err(ForbiddenSubgraph::P4(path)) => {
+                    assert_induces(&graph, &path, &[(0, 1), (1, 2), (2, 3)])
+                }
+                Err(other) => panic!("expected a P4, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn empty_graph() {
+        let graph = Graph::new();
+        let cotree = Cotree::new(&graph).unwrap();
+
+        assert!(cotree.is_empty());
+        assert_eq!(cotree.clique_number(), 0);
+        assert!(cotree.color().is_empty());
+    }
+}
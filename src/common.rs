@@ -1,18 +1,115 @@
-use std::cmp::{Ordering, Reverse};
-use std::collections::{BTreeSet, HashSet};
+use std::collections::HashSet;
 use std::error::Error;
+use std::fmt;
 use std::io::BufRead;
 
 use petgraph::csr::Csr;
 use petgraph::Undirected;
 
-// Everything that was written here was wrong. It's just standard lexicographical comparsion
-// However, as we've wrapped the indices in Reverse to build a set, we have to undo that
-pub fn rose_cmp(a: &BTreeSet<Reverse<usize>>, b: &BTreeSet<Reverse<usize>>) -> Ordering {
-    a.iter()
-        .map(|x| x.0)
-        .cmp(b.iter().map(|y| y.0))
-        .then(a.len().cmp(&b.len()))
+// Certificate that a graph is not chordal: a chordless cycle of length at least 4,
+// given as its vertices in cycle order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotChordal {
+    pub hole: Vec<u32>,
+}
+
+impl fmt::Display for NotChordal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "graph is not chordal, it has the hole {:?}", self.hole)
+    }
+}
+
+impl Error for NotChordal {}
+
+// A small induced subgraph certifying that a graph is not in some class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForbiddenSubgraph {
+    // two edges, v0-v1 and v2-v3, with nothing between them
+    TwoK2([u32; 4]),
+    // a chordless path, in path order
+    P4([u32; 4]),
+    // a C4, in cycle order, with the chord v1-v3
+    Diamond([u32; 4]),
+    // a P4, in path order, and v4 adjacent to all of it
+    Gem([u32; 5]),
+    // cycles, in cycle order
+    C4([u32; 4]),
+    C5([u32; 5]),
+    // a chordless cycle of any length from 4 up, in cycle order
+    Hole(Vec<u32>),
+    // a k-sun, k >= 3: the clique w0, ..., wk-1 and the independent set u0, ..., uk-1,
+    // where each ui is adjacent to exactly wi and wi+1 (mod k)
+    Sun {
+        clique: Vec<u32>,
+        independent_set: Vec<u32>,
+    },
+}
+
+impl From<NotChordal> for ForbiddenSubgraph {
+    fn from(error: NotChordal) -> ForbiddenSubgraph {
+        ForbiddenSubgraph::Hole(error.hole)
+    }
+}
+
+// Inverse of an elimination scheme, mapping each vertex to its position
+pub fn scheme_positions(scheme: &[i32]) -> Vec<usize> {
+    let mut position = vec![0; scheme.len()];
+
+    for (i, &v) in scheme.iter().enumerate() {
+        position[v as usize] = i;
+    }
+
+    position
+}
+
+// Whether a scheme holds every vertex of a graph on n vertices exactly once
+pub fn is_scheme(scheme: &[i32], n: usize) -> bool {
+    let mut seen = vec![false; n];
+
+    scheme.len() == n
+        && scheme
+            .iter()
+            .all(|&v| v >= 0 && (v as usize) < n && !std::mem::replace(&mut seen[v as usize], true))
+}
+
+// The parent of v in an elimination scheme: its earliest eliminated later neighbour
+pub fn scheme_parent(graph: &Csr<(), (), Undirected>, position: &[usize], v: u32) -> Option<u32> {
+    graph
+        .neighbors_slice(v)
+        .iter()
+        .cloned()
+        .filter(|&w| position[w as usize] > position[v as usize])
+        .min_by_key(|&w| position[w as usize])
+}
+
+// Tarjan and Yannakakis' test for the children of a single vertex p of an elimination scheme,
+// its earlier neighbours whose parent it is. The scheme is a PES iff, for every p, the later
+// neighbours of its children are all adjacent to p, or p itself
+// Those are checked against marked[w] == p, set here for the neighbours of p, so that a vertex
+// costs O(1) plus its degree, once for itself and once as a child. `marked` can be reused across
+// calls, as long as no other vertex marks it meanwhile
+pub fn children_covered(
+    graph: &Csr<(), (), Undirected>,
+    position: &[usize],
+    parents: &[Option<u32>],
+    p: u32,
+    marked: &mut [usize],
+) -> bool {
+    let neighbours = graph.neighbors_slice(p);
+    for &w in neighbours {
+        marked[w as usize] = p as usize;
+    }
+
+    neighbours
+        .iter()
+        .filter(|&&child| parents[child as usize] == Some(p))
+        .all(|&child| {
+            graph
+                .neighbors_slice(child)
+                .iter()
+                .filter(|&&w| position[w as usize] > position[child as usize] && w != p)
+                .all(|&w| marked[w as usize] == p as usize)
+        })
 }
 
 pub fn complete_graph_edge_count(vertex_count: usize) -> usize {
@@ -39,11 +136,70 @@ pub fn graph_from_reader(reader: impl BufRead) -> Result<Csr<(), (), Undirected>
             });
     }
 
+    Ok(graph_from_edges(node_count, edges))
+}
+
+// Reads a 0/1 matrix, one row per line, which unlike an adjacency matrix needn't be square
+// Every row must have the same length
+pub fn matrix_from_reader(reader: impl BufRead) -> Result<Vec<Vec<bool>>, Box<dyn Error>> {
+    let mut matrix: Vec<Vec<bool>> = Vec::new();
+
+    for (row, line_str) in reader.lines().enumerate() {
+        let line_str = line_str?;
+        let elements: Vec<bool> = line_str
+            .split_ascii_whitespace()
+            .map(|value| value == "1")
+            .collect();
+
+        if let Some(first) = matrix.first() {
+            if first.len() != elements.len() {
+                return Err(format!(
+                    "row {} has {} columns, but row 0 has {}",
+                    row,
+                    elements.len(),
+                    first.len()
+                )
+                .into());
+            }
+        }
+
+        matrix.push(elements);
+    }
+
+    Ok(matrix)
+}
+
+pub fn graph_from_edges(
+    node_count: usize,
+    edges: impl IntoIterator<Item = (u32, u32)>,
+) -> Csr<(), (), Undirected> {
     let mut graph = Csr::with_nodes(node_count);
 
-    for (v, w) in edges.into_iter() {
+    for (v, w) in edges {
         graph.add_edge(v, w, ());
     }
 
-    Ok(graph)
+    graph
+}
+
+// The subgraph induced by vertices, relabelled so that vertices[i] becomes i
+pub fn induced_subgraph(
+    graph: &Csr<(), (), Undirected>,
+    vertices: &[u32],
+) -> Csr<(), (), Undirected> {
+    let mut label = vec![None; graph.node_count()];
+    for (i, &v) in vertices.iter().enumerate() {
+        label[v as usize] = Some(i as u32);
+    }
+
+    let edges = vertices.iter().flat_map(|&v| {
+        let label = &label;
+        graph
+            .neighbors_slice(v)
+            .iter()
+            .filter(move |&&w| v < w)
+            .filter_map(move |&w| label[w as usize].map(|w| (label[v as usize].unwrap(), w)))
+    });
+
+    graph_from_edges(vertices.len(), edges)
 }
@@ -0,0 +1,239 @@
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::common::{is_scheme, scheme_positions};
+
+type Graph = Csr<(), (), Undirected>;
+
+// An ordering of the vertices where each one has a maximum neighbour among the ones left,
+// and a spanning tree where every closed neighbourhood induces a subtree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaximumNeighbourhoodOrdering {
+    // in elimination order, the first vertex being eliminated first
+    pub ordering: Vec<i32>,
+    // a maximum neighbour of each vertex, among the ones left when it is eliminated,
+    // which may be the vertex itself
+    pub maximum_neighbours: Vec<u32>,
+    // the parent of each vertex in the spanning tree, None for one vertex of each component
+    pub tree: Vec<Option<u32>>,
+}
+
+// Brandstädt, Dragan, Chepoi and Voloshin: G is dually chordal iff the hypergraph of its
+// closed neighbourhoods is α-acyclic. That hypergraph is its own dual, as v is in N[w] iff
+// w is in N[v], so a join tree of it is a tree on the vertices of G where every N[v] induces
+// a subtree. Its edges are edges of G, since an edge of the tree between nonadjacent a and b
+// would split it in two sides with no edge of G between them
+// We run Tarjan and Yannakakis' Maximum Cardinality Search on the hyperedges: select the N[a]
+// with the most numbered vertices, and number the rest of them, a first. The hypergraph is
+// acyclic iff the vertices of each N[a] numbered before it lie in the N[b] that numbered the
+// last of them, which then is the parent of a in the join tree
+// The numbering, read backwards, is a perfect elimination ordering of the 2-section, here the
+// square of G. Eliminating v removes no distance up to 2, as its neighbours are all adjacent
+// to its maximum neighbour, so the N[a] that numbered v still holds everything at distance 2
+// from v when it is eliminated, making a its maximum neighbour. This is O(n + m)
+pub fn maximum_neighbourhood_ordering(graph: &Graph) -> Option<MaximumNeighbourhoodOrdering> {
+    let n = graph.node_count();
+    let closed = |v: u32| {
+        Some(v)
+            .into_iter()
+            .chain(graph.neighbors_slice(v).iter().cloned())
+    };
+
+    // number of numbered vertices of each N[v], and the step that numbered each vertex
+    let mut weight = vec![0; n];
+    let mut numbered_by: Vec<Option<usize>> = vec![None; n];
+    let mut selected = vec![false; n];
+    let mut selection = Vec::with_capacity(n);
+    let mut numbering = Vec::with_capacity(n);
+
+    // the buckets keep stale entries, which we skip, as each update adds a new one
+    let mut bucket: Vec<Vec<u32>> = vec![Vec::new(); n + 1];
+    bucket[0].extend((0..n as u32).rev());
+    let mut max_weight = 0;
+
+    for i in 0..n {
+        let a = loop {
+            match bucket[max_weight].pop() {
+                Some(a) if !selected[a as usize] && weight[a as usize] == max_weight => break a,
+                Some(_) => {}
+                None => max_weight -= 1,
+            }
+        };
+        selected[a as usize] = true;
+        selection.push(a);
+
+        for x in closed(a) {
+            if numbered_by[x as usize].is_some() {
+                continue;
+            }
+            numbered_by[x as usize] = Some(i);
+            numbering.push(x);
+
+            for b in closed(x).filter(|&b| !selected[b as usize]) {
+                weight[b as usize] += 1;
+                bucket[weight[b as usize]].push(b);
+                max_weight = max_weight.max(weight[b as usize]);
+            }
+        }
+    }
+    let numbered_by: Vec<usize> = numbered_by.into_iter().map(Option::unwrap).collect();
+
+    // the vertices of each N[a] numbered before it, to look up in the N[b] of their parent
+    let mut tree = vec![None; n];
+    let mut queries: Vec<Vec<u32>> = vec![Vec::new(); n];
+    for (i, &a) in selection.iter().enumerate() {
+        let earlier = || closed(a).filter(|&x| numbered_by[x as usize] < i);
+
+        if let Some(j) = earlier().map(|x| numbered_by[x as usize]).max() {
+            tree[a as usize] = Some(selection[j]);
+            queries[j].extend(earlier());
+        }
+    }
+
+    let mut marked = vec![false; n];
+    for (j, &b) in selection.iter().enumerate() {
+        closed(b).for_each(|x| marked[x as usize] = true);
+        if !queries[j].iter().all(|&x| marked[x as usize]) {
+            return None;
+        }
+        closed(b).for_each(|x| marked[x as usize] = false);
+    }
+
+    Some(MaximumNeighbourhoodOrdering {
+        ordering: numbering.into_iter().rev().map(|v| v as i32).collect(),
+        maximum_neighbours: numbered_by.into_iter().map(|i| selection[i]).collect(),
+        tree,
+    })
+}
+
+pub fn is_dually_chordal(graph: &Graph) -> bool {
+    maximum_neighbourhood_ordering(graph).is_some()
+}
+
+// Checks that every vertex v of the ordering has a maximum neighbour among the vertices left,
+// some u in N[v] with N[w] ⊆ N[u] for every w in N[v], all restricted to the vertices left
+// If there is one, a vertex of largest degree in N[v] is one. This is O(Σ d(v)² log n)
+pub fn is_maximum_neighbourhood_ordering(ordering: &[i32], graph: &Graph) -> bool {
+    if !is_scheme(ordering, graph.node_count()) {
+        return false;
+    }
+
+    let position = &scheme_positions(ordering);
+
+    ordering.iter().all(|&v| {
+        let first = position[v as usize];
+        let left = |w: u32| {
+            graph
+                .neighbors_slice(w)
+                .iter()
+                .cloned()
+                .chain(Some(w))
+                .filter(move |&x| position[x as usize] >= first)
+        };
+
+        let u = left(v as u32).max_by_key(|&w| left(w).count()).unwrap();
+
+        left(v as u32).all(|w| left(w).all(|x| x == u || graph.contains_edge(u, x)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::graph_from_edges;
+
+    // every N[v] must induce a subtree of the spanning tree, having one less edge than vertices
+    fn assert_neighbourhood_subtrees(graph: &Graph, parent: &[Option<u32>]) {
+        for v in 0..graph.node_count() as u32 {
+            let inside = |w: u32| w == v || graph.contains_edge(v, w);
+            let edges = (0..graph.node_count() as u32)
+                .filter(|&w| inside(w) && matches!(parent[w as usize], Some(p) if inside(p)))
+                .count();
+
+            assert_eq!(edges, graph.neighbors_slice(v).len());
+        }
+    }
+
+    fn assert_spanning_tree(graph: &Graph, parent: &[Option<u32>]) {
+        for (v, p) in parent.iter().enumerate() {
+            if let Some(p) = *p {
+                assert!(graph.contains_edge(v as u32, p));
+            }
+        }
+        assert_neighbourhood_subtrees(graph, parent);
+    }
+
+    #[test]
+    fn chordal_and_not() {
+        // the 3-sun, with a vertex seeing all of it
+        let mut edges = vec![
+            (0, 1),
+            (1, 2),
+            (0, 2),
+            (3, 0),
+            (3, 1),
+            (4, 1),
+            (4, 2),
+            (5, 2),
+            (5, 0),
+        ];
+        edges.extend((0..6).map(|v| (v, 6)));
+        let graph = graph_from_edges(7, edges.clone());
+        let found = maximum_neighbourhood_ordering(&graph).unwrap();
+
+        println!("{:?}", found);
+
+        assert!(is_maximum_neighbourhood_ordering(&found.ordering, &graph));
+        assert_spanning_tree(&graph, &found.tree);
+        assert_eq!(found.tree.iter().filter(|p| p.is_none()).count(), 1);
+        for (v, &u) in found.maximum_neighbours.iter().enumerate() {
+            assert!(u == v as u32 || graph.contains_edge(u, v as u32));
+        }
+
+        // without it, the sun is chordal, but no vertex has a maximum neighbour
+        edges.truncate(9);
+        assert!(!is_dually_chordal(&graph_from_edges(6, edges)));
+
+        // a C4 with a vertex seeing all of it, isn't chordal, but is dually chordal
+        let wheel = graph_from_edges(
+            5,
+            vec![
+                (0, 1),
+                (1, 2),
+                (2, 3),
+                (3, 0),
+                (4, 0),
+                (4, 1),
+                (4, 2),
+                (4, 3),
+            ],
+        );
+        assert!(is_dually_chordal(&wheel));
+
+        // no cycle of length at least 5 is
+        let cycle = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        assert!(!is_dually_chordal(&cycle));
+        assert!(!is_maximum_neighbourhood_ordering(&[0, 1, 2, 3, 4], &cycle));
+    }
+
+    #[test]
+    fn bad_orderings() {
+        // a path 0 - 1 - 2
+        let graph = graph_from_edges(3, vec![(0, 1), (1, 2)]);
+
+        assert!(is_maximum_neighbourhood_ordering(&[0, 1, 2], &graph));
+        assert!(!is_maximum_neighbourhood_ordering(&[0, 0, 1], &graph));
+        assert!(!is_maximum_neighbourhood_ordering(&[0, 1, 7], &graph));
+        assert!(!is_maximum_neighbourhood_ordering(&[0, 1], &graph));
+    }
+
+    #[test]
+    fn forest() {
+        let graph = graph_from_edges(7, vec![(0, 1), (0, 2), (1, 3), (4, 5)]);
+        let found = maximum_neighbourhood_ordering(&graph).unwrap();
+
+        assert!(is_maximum_neighbourhood_ordering(&found.ordering, &graph));
+        assert_spanning_tree(&graph, &found.tree);
+        assert_eq!(found.tree.iter().filter(|p| p.is_none()).count(), 3);
+    }
+}
@@ -0,0 +1,665 @@
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::clique_tree::CliqueTree;
+use crate::common::{graph_from_edges, is_scheme, scheme_positions};
+use crate::serial::{MultiSweep, Sweep};
+
+type Graph = Csr<(), (), Undirected>;
+
+const NIL: usize = usize::MAX;
+
+// An interval model, as one closed [start, end] interval per vertex
+pub type IntervalModel = Vec<(usize, usize)>;
+
+// Recognition of interval graphs, with a model when there is one
+// By Gilmore and Hoffman, a graph is an interval graph iff its maximal cliques can be put in
+// a row where the cliques containing each vertex are consecutive. Numbering the cliques along
+// the row then stretches every vertex from its first clique to its last
+// We look for the row as Habib, McConnell, Paul and Viennot do, by refining an ordered
+// partition of the cliques of the clique tree that LexBFS gives. A vertex in the separator of
+// a tree edge between two classes is a pivot: the classes its cliques touch have to be
+// consecutive, and the first and last of them are split so that its cliques stay together.
+// With no pivots left, every class meets the others in vertices that all of its cliques have,
+// so the clique of a class found last by LexBFS can go at an end of it
+// Each pivot costs its number of cliques, and each split the tree edges of its smaller side,
+// so this is O(n log n + m). The row is checked before building the model
+pub fn interval_model(graph: &Graph) -> Option<IntervalModel> {
+    let tree = CliqueTree::new(graph).ok()?;
+
+    // the cliques of each vertex, in increasing order
+    let mut cliques_of = vec![Vec::new(); graph.node_count()];
+    for (index, clique) in tree.cliques().enumerate() {
+        for &v in clique {
+            cliques_of[v as usize].push(index);
+        }
+    }
+
+    let position = clique_row(&tree, &cliques_of)?;
+
+    cliques_of
+        .iter()
+        .map(|cliques| {
+            let first = cliques.iter().map(|&c| position[c]).min()?;
+            let last = cliques.iter().map(|&c| position[c]).max()?;
+
+            if last - first + 1 == cliques.len() {
+                Some((first, last))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn is_interval(graph: &Graph) -> bool {
+    interval_model(graph).is_some()
+}
+
+// The position of every clique in the row found by refining, or None if a pivot shows that
+// there is no row
+fn clique_row(tree: &CliqueTree, cliques_of: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let k = tree.len();
+
+    // the tree edges at each clique, as the clique across and the child, whose separator it is
+    let mut edges = vec![Vec::new(); k];
+    for (child, parent, _) in tree.separators() {
+        edges[child].push((parent, child));
+        edges[parent].push((child, child));
+    }
+
+    let mut partition = CliquePartition::new(k);
+    // classes that may have more than one clique
+    let mut candidates = vec![0];
+    let mut pivots: Vec<usize> = Vec::new();
+    let mut queued = vec![false; cliques_of.len()];
+    // touched[class] is the number of cliques of the current pivot in it
+    let mut touched = vec![0; k];
+
+    let mut queue_crossing =
+        |partition: &CliquePartition, a: usize, b: usize, pivots: &mut Vec<usize>| {
+            let (smaller, other) = if partition.classes[a].size <= partition.classes[b].size {
+                (a, b)
+            } else {
+                (b, a)
+            };
+
+            for c in partition.members(smaller) {
+                for &(d, child) in &edges[c] {
+                    if partition.class[d] != other {
+                        continue;
+                    }
+
+                    for &v in tree.separator(child) {
+                        if !std::mem::replace(&mut queued[v as usize], true) {
+                            pivots.push(v as usize);
+                        }
+                    }
+                }
+            }
+        };
+
+    loop {
+        if let Some(x) = pivots.pop() {
+            let cliques = &cliques_of[x];
+
+            let mut classes = Vec::new();
+            for &c in cliques {
+                let class = partition.class[c];
+                if touched[class] == 0 {
+                    classes.push(class);
+                }
+                touched[class] += 1;
+            }
+
+            let is_touched = |class: usize| class != NIL && touched[class] > 0;
+            let partial = |class: usize| touched[class] < partition.classes[class].size;
+            let first = classes
+                .iter()
+                .cloned()
+                .filter(|&class| !is_touched(partition.classes[class].prev))
+                .collect::<Vec<_>>();
+            let last = classes
+                .iter()
+                .cloned()
+                .find(|&class| !is_touched(partition.classes[class].next));
+
+            // the touched classes must be a run, only partly made of cliques of x at its ends
+            let (first, last) = match (first.as_slice(), last) {
+                (&[first], Some(last)) => (first, last),
+                _ => return None,
+            };
+            if classes
+                .iter()
+                .any(|&class| class != first && class != last && partial(class))
+            {
+                return None;
+            }
+
+            // the cliques of x go to the end of the first class, and the start of the last one
+            let splits: Vec<(usize, bool)> = [(first, true), (last, false)]
+                .iter()
+                .cloned()
+                .filter(|&(class, _)| first != last && partial(class))
+                .collect();
+            for &class in &classes {
+                touched[class] = 0;
+            }
+
+            for (class, after) in splits {
+                let inside: Vec<usize> = cliques
+                    .iter()
+                    .cloned()
+                    .filter(|&c| partition.class[c] == class)
+                    .collect();
+                let split = partition.split(&inside, after);
+
+                candidates.extend(&[class, split]);
+                queue_crossing(&partition, class, split, &mut pivots);
+            }
+
+            continue;
+        }
+
+        let class = loop {
+            match candidates.pop() {
+                Some(class) if partition.classes[class].size > 1 => break class,
+                Some(_) => {}
+                None => return Some(partition.positions()),
+            }
+        };
+
+        // cliques are numbered in elimination order, so the smallest one was found last
+        let found_last = partition.classes[class].first;
+        let split = partition.split(&[found_last], true);
+
+        candidates.push(class);
+        queue_crossing(&partition, class, split, &mut pivots);
+    }
+}
+
+// An ordered partition of the cliques, as a list of classes, each one a list of its cliques
+// kept in increasing order
+struct CliquePartition {
+    class: Vec<usize>,
+    // the cliques before and after each one in its class
+    prev: Vec<usize>,
+    next: Vec<usize>,
+    classes: Vec<Class>,
+    first_class: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Class {
+    first: usize,
+    last: usize,
+    size: usize,
+    // the classes before and after this one
+    prev: usize,
+    next: usize,
+}
+
+impl CliquePartition {
+    // All k cliques in a single class
+    fn new(k: usize) -> CliquePartition {
+        CliquePartition {
+            class: vec![0; k],
+            prev: (0..k).map(|c| c.checked_sub(1).unwrap_or(NIL)).collect(),
+            next: (1..=k).map(|c| if c < k { c } else { NIL }).collect(),
+            classes: vec![Class {
+                first: if k > 0 { 0 } else { NIL },
+                last: k.checked_sub(1).unwrap_or(NIL),
+                size: k,
+                prev: NIL,
+                next: NIL,
+            }],
+            first_class: 0,
+        }
+    }
+
+    fn members(&self, class: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut c = self.classes[class].first;
+
+        std::iter::from_fn(move || {
+            let current = c;
+            if current == NIL {
+                return None;
+            }
+            c = self.next[current];
+            Some(current)
+        })
+    }
+
+    // Moves cliques, all from the same class and in increasing order, into a new class placed
+    // right after that one, or right before it. This is O(|cliques|)
+    fn split(&mut self, cliques: &[usize], after: bool) -> usize {
+        let old = self.class[cliques[0]];
+        let new = self.classes.len();
+
+        let (prev, next) = if after {
+            (old, self.classes[old].next)
+        } else {
+            (self.classes[old].prev, old)
+        };
+        self.classes.push(Class {
+            first: NIL,
+            last: NIL,
+            size: 0,
+            prev,
+            next,
+        });
+        match prev {
+            NIL => self.first_class = new,
+            prev => self.classes[prev].next = new,
+        }
+        if next != NIL {
+            self.classes[next].prev = new;
+        }
+
+        for &c in cliques {
+            self.remove(c);
+            self.append(new, c);
+        }
+
+        new
+    }
+
+    fn remove(&mut self, c: usize) {
+        let class = &mut self.classes[self.class[c]];
+        class.size -= 1;
+
+        match self.prev[c] {
+            NIL => class.first = self.next[c],
+            prev => self.next[prev] = self.next[c],
+        }
+        match self.next[c] {
+            NIL => class.last = self.prev[c],
+            next => self.prev[next] = self.prev[c],
+        }
+    }
+
+    fn append(&mut self, class: usize, c: usize) {
+        self.class[c] = class;
+        self.prev[c] = self.classes[class].last;
+        self.next[c] = NIL;
+
+        match self.classes[class].last {
+            NIL => self.classes[class].first = c,
+            last => self.next[last] = c,
+        }
+        self.classes[class].last = c;
+        self.classes[class].size += 1;
+    }
+
+    // The position of every clique, going through the classes in order
+    fn positions(&self) -> Vec<usize> {
+        let mut position = vec![0; self.class.len()];
+        let mut i = 0;
+        let mut class = self.first_class;
+
+        while class != NIL {
+            for c in self.members(class) {
+                position[c] = i;
+                i += 1;
+            }
+            class = self.classes[class].next;
+        }
+
+        position
+    }
+}
+
+// Builds the interval model for the visit order of a scheme, if that is an interval ordering
+// For each vertex, its later visited neighbours must be exactly the ones visited between it
+// and the last of them. This is O(n + m)
+// Returns None as well if scheme is not an ordering of the vertices
+pub fn model_from_scheme(graph: &Graph, scheme: &[i32]) -> Option<IntervalModel> {
+    let n = scheme.len();
+    if !is_scheme(scheme, graph.node_count()) {
+        return None;
+    }
+
+    let position = scheme_positions(scheme);
+    let visit = |v: u32| n - 1 - position[v as usize];
+
+    let mut model = Vec::with_capacity(n);
+    for v in 0..n as u32 {
+        let start = visit(v);
+        let later = graph
+            .neighbors_slice(v)
+            .iter()
+            .filter(|&&w| visit(w) > start);
+
+        let end = later.clone().map(|&w| visit(w)).max().unwrap_or(start);
+        if later.count() != end - start {
+            return None;
+        }
+
+        model.push((start, end));
+    }
+
+    Some(model)
+}
+
+// A unit interval model, where every vertex v gets [starts[v], starts[v] + length]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitIntervalModel {
+    // a proper interval ordering: every closed neighbourhood is consecutive in it
+    pub ordering: Vec<i32>,
+    pub length: usize,
+    pub starts: Vec<usize>,
+}
+
+impl UnitIntervalModel {
+    pub fn intervals(&self) -> IntervalModel {
+        self.starts
+            .iter()
+            .map(|&start| (start, start + self.length))
+            .collect()
+    }
+}
+
+// Corneil's 3-sweep recognition of proper (unit) interval graphs
+// The third of the LexBFS, LexBFS+, LexBFS+ sweeps is a proper interval ordering iff
+// the graph has one. This is O(n + m)
+pub fn unit_interval_model(graph: &Graph) -> Option<UnitIntervalModel> {
+    let ordering = MultiSweep::new(vec![Sweep::LexBfs, Sweep::LexBfsPlus, Sweep::LexBfsPlus])
+        .last(graph)
+        .unwrap();
+
+    let reach = proper_reach(graph, &ordering)?;
+    let starts = unit_starts(&ordering, &reach);
+
+    Some(UnitIntervalModel {
+        ordering,
+        length: graph.node_count(),
+        starts,
+    })
+}
+
+pub fn is_proper_interval(graph: &Graph) -> bool {
+    unit_interval_model(graph).is_some()
+}
+
+// For a proper interval ordering, the position of the last vertex of each closed neighbourhood
+fn proper_reach(graph: &Graph, ordering: &[i32]) -> Option<Vec<usize>> {
+    let position = scheme_positions(ordering);
+    let mut reach = vec![0; ordering.len()];
+
+    for (i, &v) in ordering.iter().enumerate() {
+        let neighbours = graph.neighbors_slice(v as u32);
+        let positions = neighbours.iter().map(|&w| position[w as usize]);
+
+        let first = positions.clone().min().unwrap_or(i).min(i);
+        let last = positions.max().unwrap_or(i).max(i);
+        if last - first != neighbours.len() {
+            return None;
+        }
+
+        reach[i] = last;
+    }
+
+    Some(reach)
+}
+
+// In a proper interval ordering, the BFS layers from the first vertex of each component are
+// consecutive cliques, and a vertex only sees a prefix of the next layer
+// Each vertex starts at n times its layer plus an offset below n. Vertices in consecutive layers
+// are then adjacent iff the later one has the smaller offset, so we order the offsets by
+// merging each pair of layers according to their adjacencies. This is O(n)
+fn unit_starts(ordering: &[i32], reach: &[usize]) -> Vec<usize> {
+    let n = ordering.len();
+
+    // layer of each position, with a gap between components so they don't touch
+    let mut layer = vec![0; n];
+    let mut start = 0;
+    let mut current = 0;
+    while start < n {
+        let mut end = start;
+        loop {
+            for l in &mut layer[start..=end] {
+                *l = current;
+            }
+
+            current += 1;
+            if reach[end] == end {
+                break;
+            }
+
+            start = end + 1;
+            end = reach[end];
+        }
+
+        current += 1;
+        start = end + 1;
+    }
+
+    // offsets must increase along each layer, and for u in the layer before w,
+    // w must be before u iff they are adjacent
+    let mut successors = vec![Vec::new(); n];
+    let mut predecessors = vec![0; n];
+    let mut precede = |a: usize, b: usize| {
+        successors[a].push(b);
+        predecessors[b] += 1;
+    };
+
+    for i in 0..n {
+        if i + 1 < n && layer[i + 1] == layer[i] {
+            precede(i, i + 1);
+        }
+
+        if layer[reach[i]] == layer[i] + 1 {
+            precede(reach[i], i);
+        }
+
+        if reach[i] + 1 < n && layer[reach[i] + 1] == layer[i] + 1 {
+            precede(i, reach[i] + 1);
+        }
+    }
+
+    // any topological order will do for the offsets
+    let mut offset = vec![0; n];
+    let mut ready: Vec<_> = (0..n).filter(|&i| predecessors[i] == 0).collect();
+    let mut next_offset = 0;
+    while let Some(i) = ready.pop() {
+        offset[i] = next_offset;
+        next_offset += 1;
+
+        for &j in &successors[i] {
+            predecessors[j] -= 1;
+            if predecessors[j] == 0 {
+                ready.push(j);
+            }
+        }
+    }
+
+    let mut starts = vec![0; n];
+    for (i, &v) in ordering.iter().enumerate() {
+        starts[v as usize] = layer[i] * n + offset[i];
+    }
+
+    starts
+}
+
+// The intersection graph of a set of closed intervals. This is O(n log n + m)
+pub fn graph_from_intervals(model: &[(usize, usize)]) -> Graph {
+    let mut by_start: Vec<_> = (0..model.len()).collect();
+    by_start.sort_by_key(|&v| model[v].0);
+
+    let mut edges = Vec::new();
+    for (i, &v) in by_start.iter().enumerate() {
+        // every interval starting within v intersects it
+        edges.extend(
+            by_start[i + 1..]
+                .iter()
+                .take_while(|&&w| model[w].0 <= model[v].1)
+                .map(|&w| (v as u32, w as u32)),
+        );
+    }
+
+    graph_from_edges(model.len(), edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serial::is_chordal;
+    use crate::test_util::{random, same_graph};
+
+    #[test]
+    fn gem_graph_interval() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(b, d, ());
+        graph.add_edge(b, e, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, e, ());
+        graph.add_edge(e, a, ());
+
+        let model = interval_model(&graph).expect("the gem is an interval graph");
+
+        println!("{:?}", model);
+
+        assert!(same_graph(&graph_from_intervals(&model), &graph));
+    }
+
+    #[test]
+    fn tree_is_not_interval() {
+        // the subdivided claw is chordal, but has an asteroidal triple
+        let graph = graph_from_edges(7, vec![(0, 1), (1, 2), (0, 3), (3, 4), (0, 5), (5, 6)]);
+
+        assert!(is_chordal(&graph));
+        assert!(!is_interval(&graph));
+    }
+
+    #[test]
+    fn cycle_is_not_interval() {
+        let graph = graph_from_edges(5, (0..5).map(|v| (v, (v + 1) % 5)));
+
+        assert_eq!(interval_model(&graph), None);
+    }
+
+    #[test]
+    fn model_round_trip() {
+        let model = vec![(0, 3), (1, 1), (2, 6), (4, 5), (7, 9), (8, 8)];
+
+        let graph = graph_from_intervals(&model);
+        let rebuilt = interval_model(&graph).unwrap();
+
+        assert!(same_graph(&graph_from_intervals(&rebuilt), &graph));
+    }
+
+    #[test]
+    fn random_model_round_trip() {
+        let mut state = 0x2545_f491_4f6c_dd1d;
+
+        for _ in 0..500 {
+            let n = 1 + random(&mut state, 30);
+            let model: IntervalModel = (0..n)
+                .map(|_| {
+                    let start = random(&mut state, 2 * n);
+                    (start, start + random(&mut state, n / 2 + 1))
+                })
+                .collect();
+
+            let graph = graph_from_intervals(&model);
+
+            let rebuilt = interval_model(&graph)
+                .expect("intersection graphs of intervals are interval graphs");
+            assert!(same_graph(&graph_from_intervals(&rebuilt), &graph));
+        }
+    }
+
+    #[test]
+    fn random_trees() {
+        // a tree is an interval graph iff it is a caterpillar
+        let mut state = 0x9e37_79b9_7f4a_7c15;
+
+        for _ in 0..500 {
+            let n = 1 + random(&mut state, 20);
+            let edges: Vec<_> = (1..n)
+                .map(|v| (random(&mut state, v) as u32, v as u32))
+                .collect();
+            let graph = graph_from_edges(n, edges);
+
+            let spine: Vec<_> = (0..n as u32)
+                .filter(|&v| graph.neighbors_slice(v).len() > 1)
+                .collect();
+            let caterpillar = spine.iter().all(|&v| {
+                graph
+                    .neighbors_slice(v)
+                    .iter()
+                    .filter(|&&w| graph.neighbors_slice(w).len() > 1)
+                    .count()
+                    <= 2
+            });
+
+            match interval_model(&graph) {
+                Some(model) => {
+                    assert!(caterpillar);
+                    assert!(same_graph(&graph_from_intervals(&model), &graph));
+                }
+                None => assert!(!caterpillar),
+            }
+        }
+    }
+
+    #[test]
+    fn scheme_models() {
+        let graph = graph_from_edges(3, vec![(0, 1), (1, 2)]);
+
+        // visited 2, 1, 0 along the path
+        assert_eq!(
+            model_from_scheme(&graph, &[0, 1, 2]),
+            Some(vec![(2, 2), (1, 2), (0, 1)])
+        );
+        // visiting 1 last leaves a gap between 2 and 0
+        assert_eq!(model_from_scheme(&graph, &[1, 0, 2]), None);
+        assert_eq!(model_from_scheme(&graph, &[0, 0, 1]), None);
+        assert_eq!(model_from_scheme(&graph, &[0, 1, 7]), None);
+        assert_eq!(model_from_scheme(&graph, &[0, 1]), None);
+    }
+
+    #[test]
+    fn path_unit_interval() {
+        let graph = graph_from_edges(6, (0..5).map(|v| (v, v + 1)));
+
+        let model = unit_interval_model(&graph).expect("paths are unit interval graphs");
+
+        println!("{:?}", model);
+
+        assert!(same_graph(
+            &graph_from_intervals(&model.intervals()),
+            &graph
+        ));
+    }
+
+    #[test]
+    fn disconnected_unit_interval() {
+        // two triangles sharing an edge, and a disjoint edge
+        let graph = graph_from_edges(6, vec![(0, 1), (1, 2), (2, 0), (1, 3), (2, 3), (4, 5)]);
+
+        let model = unit_interval_model(&graph).unwrap();
+
+        assert!(same_graph(
+            &graph_from_intervals(&model.intervals()),
+            &graph
+        ));
+    }
+
+    #[test]
+    fn claw_is_not_proper_interval() {
+        let graph = graph_from_edges(4, vec![(0, 1), (0, 2), (0, 3)]);
+
+        assert!(is_interval(&graph));
+        assert!(!is_proper_interval(&graph));
+    }
+}
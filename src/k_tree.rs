@@ -0,0 +1,443 @@
+use std::collections::BTreeSet;
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::common::{graph_from_edges, scheme_positions};
+use crate::serial::{is_pes, lex_bfs};
+
+type Graph = Csr<(), (), Undirected>;
+
+// A k-tree is built from a k-clique by adding vertices adjacent to exactly a k-clique of
+// the ones before (so K_k counts as one). Read backwards, that is a PES where every vertex
+// but the last k has exactly k later neighbours, and the last k are a clique
+// Every PES of a k-tree looks like that: a vertex and its later neighbours are a clique,
+// which has at most k + 1 vertices, and anything less would leave fewer than the
+// kn - k(k + 1)/2 edges a k-tree has. So checking the LexBFS one is enough. This is O(n + m)
+pub fn is_k_tree(graph: &Graph, k: usize) -> bool {
+    let n = graph.node_count();
+    if n < k {
+        return false;
+    }
+
+    let scheme = lex_bfs(graph);
+    if !is_pes(&scheme, graph) {
+        return false;
+    }
+    let position = scheme_positions(&scheme);
+
+    scheme.iter().enumerate().all(|(i, &v)| {
+        let later = graph
+            .neighbors_slice(v as u32)
+            .iter()
+            .filter(|&&w| position[w as usize] > i)
+            .count();
+
+        later == k.min(n - 1 - i)
+    })
+}
+
+// The partial k-trees are the subgraphs of k-trees, or the graphs of treewidth at most k
+// Arnborg and Proskurowski: for k <= 3, a graph is a partial k-tree iff it can be reduced to
+// nothing by deleting vertices of degree at most min(k, 2), replacing a vertex of degree 2 by
+// an edge between its neighbours, plus, for k = 3, the following rules on vertices of
+// degree 3, all of which keep the treewidth at most 3:
+// - triangle: one with an edge among its neighbours a, b and c is deleted, making them a clique
+// - buddy: two of them with the same neighbours a, b and c are deleted, making those a clique
+// - cube: one, c, whose neighbours a1, a2 and a3 have degree 3, where each ai is adjacent to
+//   two of b1, b2 and b3, all but bi, is deleted together with them, making the bi a clique
+// Every rule eliminates vertices with at most k neighbours, making those a clique, so the
+// rules add up to an elimination scheme of width at most k, which we then extend to a k-tree
+// Returns that k-tree, containing the graph, or None if there is none. Graphs with fewer than
+// k vertices are partial k-trees, but no k-tree on their vertices exists, so we return the
+// complete graph on them instead
+// There are no such rules for k > 3, so we return None for those too. After each rule, we
+// only look again at the vertices near the ones it touched, which is O(n³) at worst, but
+// much less on sparse graphs
+pub fn partial_k_tree(graph: &Graph, k: usize) -> Option<Graph> {
+    if k > 3 {
+        return None;
+    }
+
+    let n = graph.node_count();
+    let mut reduction = Reduction {
+        adjacency: (0..n as u32)
+            .map(|v| graph.neighbors_slice(v).iter().cloned().collect())
+            .collect(),
+        eliminated: vec![false; n],
+        scheme: Vec::with_capacity(n),
+        later: vec![Vec::new(); n],
+    };
+
+    let mut pending: Vec<u32> = (0..n as u32).rev().collect();
+    while let Some(v) = pending.pop() {
+        if reduction.eliminated[v as usize] {
+            continue;
+        }
+
+        let eliminated = reduction.scheme.len();
+        if !reduction.reduce(v, k) {
+            continue;
+        }
+
+        // anything within distance 2 of what changed may now be reducible
+        for i in eliminated..reduction.scheme.len() {
+            let v = reduction.scheme[i] as usize;
+            for &w in &reduction.later[v] {
+                pending.push(w);
+                pending.extend(reduction.adjacency[w as usize].iter().cloned());
+            }
+        }
+    }
+
+    if reduction.scheme.len() < n {
+        return None;
+    }
+
+    Some(k_tree_from_scheme(&reduction.scheme, &reduction.later, k))
+}
+
+pub fn is_partial_k_tree(graph: &Graph, k: usize) -> bool {
+    partial_k_tree(graph, k).is_some()
+}
+
+// The graph as the reduction rules leave it, and the vertices they eliminated so far,
+// each with its neighbours when it was eliminated
+struct Reduction {
+    adjacency: Vec<BTreeSet<u32>>,
+    eliminated: Vec<bool>,
+    scheme: Vec<i32>,
+    later: Vec<Vec<u32>>,
+}
+
+impl Reduction {
+    fn degree(&self, v: u32) -> usize {
+        self.adjacency[v as usize].len()
+    }
+
+    fn neighbours(&self, v: u32) -> Vec<u32> {
+        self.adjacency[v as usize].iter().cloned().collect()
+    }
+
+    fn adjacent(&self, v: u32, w: u32) -> bool {
+        self.adjacency[v as usize].contains(&w)
+    }
+
+    // Makes the neighbours of v a clique, and removes v
+    fn eliminate(&mut self, v: u32) {
+        let neighbours = self.neighbours(v);
+
+        for &a in &neighbours {
+            self.adjacency[a as usize].remove(&v);
+            for &b in &neighbours {
+                if a != b {
+                    self.adjacency[a as usize].insert(b);
+                }
+            }
+        }
+
+        self.adjacency[v as usize].clear();
+        self.eliminated[v as usize] = true;
+        self.scheme.push(v as i32);
+        self.later[v as usize] = neighbours;
+    }
+
+    // Applies the first rule that fits around v, if any
+    fn reduce(&mut self, v: u32, k: usize) -> bool {
+        let degree = self.degree(v);
+
+        if degree <= k.min(2) {
+            self.eliminate(v);
+            return true;
+        }
+        if k < 3 || degree != 3 {
+            return false;
+        }
+
+        let neighbours = self.neighbours(v);
+        let (a, b, c) = (neighbours[0], neighbours[1], neighbours[2]);
+
+        // triangle
+        if self.adjacent(a, b) || self.adjacent(b, c) || self.adjacent(a, c) {
+            self.eliminate(v);
+            return true;
+        }
+
+        // buddy
+        let buddy = self.adjacency[a as usize]
+            .iter()
+            .cloned()
+            .find(|&w| w != v && self.adjacency[w as usize] == self.adjacency[v as usize]);
+        if let Some(w) = buddy {
+            self.eliminate(v);
+            self.eliminate(w);
+            return true;
+        }
+
+        // cube, with v as c
+        if let Some(cube) = self.cube(&neighbours) {
+            for &a in &cube {
+                self.eliminate(a);
+            }
+            self.eliminate(v);
+            return true;
+        }
+
+        false
+    }
+
+    // Given the neighbours ai of a vertex of degree 3, with no edges among them, checks that
+    // they have degree 3 and other neighbours as in the cube rule, returning them if so
+    fn cube(&self, neighbours: &[u32]) -> Option<Vec<u32>> {
+        if neighbours.iter().any(|&a| self.degree(a) != 3) {
+            return None;
+        }
+
+        // the two neighbours of each ai besides the centre, which all three have in common
+        let centre = neighbours
+            .iter()
+            .map(|&a| &self.adjacency[a as usize])
+            .fold(None, |common: Option<BTreeSet<u32>>, next| {
+                Some(match common {
+                    Some(common) => common.intersection(next).cloned().collect(),
+                    None => next.clone(),
+                })
+            })?;
+        let others = |a: u32| -> Vec<u32> {
+            self.adjacency[a as usize]
+                .iter()
+                .cloned()
+                .filter(|w| !centre.contains(w))
+                .collect()
+        };
+
+        let mut b: Vec<u32> = neighbours.iter().flat_map(|&a| others(a)).collect();
+        b.sort();
+        b.dedup();
+
+        // three bi, each missed by exactly one ai
+        let fits = b.len() == 3
+            && neighbours.iter().all(|&a| others(a).len() == 2)
+            && b.iter().all(|&x| {
+                neighbours
+                    .iter()
+                    .filter(|&&a| !others(a).contains(&x))
+                    .count()
+                    == 1
+            });
+
+        if fits {
+            Some(neighbours.to_vec())
+        } else {
+            None
+        }
+    }
+}
+
+// Extends an elimination scheme of width at most k, given the neighbours of each vertex when
+// it was eliminated, to a k-tree. Going backwards, the first k + 1 vertices make a clique,
+// and every later v is attached to a k-clique holding its neighbours. Those are all in the
+// (k + 1)-clique made by the latest of them, p, and the clique p was attached to, so we
+// drop one vertex of it that v doesn't need. This is O(nk)
+fn k_tree_from_scheme(scheme: &[i32], later: &[Vec<u32>], k: usize) -> Graph {
+    let n = scheme.len();
+    let order: Vec<u32> = scheme.iter().rev().map(|&v| v as u32).collect();
+    let position = scheme_positions(scheme);
+    let built = |v: u32| n - 1 - position[v as usize];
+
+    let base = &order[..n.min(k + 1)];
+    let mut edges: Vec<(u32, u32)> = base
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &a)| base[i + 1..].iter().map(move |&b| (a, b)))
+        .collect();
+
+    let mut attached: Vec<Vec<u32>> = vec![Vec::new(); n];
+    for &v in &order[base.len()..] {
+        let needed = &later[v as usize];
+
+        let mut clique = match needed.iter().cloned().max_by_key(|&w| built(w)) {
+            Some(p) if built(p) > k => {
+                let mut clique = attached[p as usize].clone();
+                clique.push(p);
+                clique
+            }
+            _ => base.to_vec(),
+        };
+        let spare = clique.iter().position(|w| !needed.contains(w)).unwrap();
+        clique.remove(spare);
+
+        edges.extend(clique.iter().map(|&w| (v, w)));
+        attached[v as usize] = clique;
+    }
+
+    graph_from_edges(n, edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::graph_from_reader;
+    use crate::test_util::random;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    fn contains(larger: &Graph, graph: &Graph) -> bool {
+        (0..graph.node_count() as u32).all(|v| {
+            graph
+                .neighbors_slice(v)
+                .iter()
+                .all(|&w| larger.contains_edge(v, w))
+        })
+    }
+
+    #[test]
+    fn k_trees() {
+        // triangles glued along edges, a 2-tree
+        let graph = graph_from_edges(
+            6,
+            vec![
+                (0, 1),
+                (1, 2),
+                (0, 2),
+                (2, 3),
+                (1, 3),
+                (3, 4),
+                (1, 4),
+                (0, 5),
+                (2, 5),
+            ],
+        );
+        assert!(is_k_tree(&graph, 2));
+        assert!(!is_k_tree(&graph, 1));
+        assert!(!is_k_tree(&graph, 3));
+
+        // a path is a 1-tree, but a forest isn't
+        assert!(is_k_tree(
+            &graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3)]),
+            1
+        ));
+        assert!(!is_k_tree(&graph_from_edges(4, vec![(0, 1), (2, 3)]), 1));
+
+        // a C4 has the right number of edges for a 2-tree, but isn't chordal
+        assert!(!is_k_tree(
+            &graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3), (3, 0)]),
+            2
+        ));
+    }
+
+    #[test]
+    fn complete_graph() {
+        let file = File::open("k10.txt").unwrap();
+        let graph = graph_from_reader(BufReader::new(file)).unwrap();
+
+        assert!(is_k_tree(&graph, 9));
+        assert!(!is_k_tree(&graph, 8));
+        assert!(!is_partial_k_tree(&graph, 3));
+    }
+
+    #[test]
+    fn partial_k_trees() {
+        // a C6 with a chord is series parallel, so it has treewidth 2
+        let graph = graph_from_edges(
+            6,
+            vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0), (0, 3)],
+        );
+        assert!(!is_partial_k_tree(&graph, 1));
+
+        let k_tree = partial_k_tree(&graph, 2).unwrap();
+        assert!(is_k_tree(&k_tree, 2));
+        assert!(contains(&k_tree, &graph));
+
+        // K4 isn't series parallel
+        let k4 = graph_from_edges(4, vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+        assert!(!is_partial_k_tree(&k4, 2));
+        assert!(is_k_tree(&partial_k_tree(&k4, 3).unwrap(), 3));
+    }
+
+    #[test]
+    fn cube() {
+        // the cube has treewidth 3, and only the cube rule reduces it
+        let cube = graph_from_edges(
+            8,
+            vec![
+                (0, 1),
+                (1, 2),
+                (2, 3),
+                (3, 0),
+                (4, 5),
+                (5, 6),
+                (6, 7),
+                (7, 4),
+                (0, 4),
+                (1, 5),
+                (2, 6),
+                (3, 7),
+            ],
+        );
+        assert!(!is_partial_k_tree(&cube, 2));
+
+        let k_tree = partial_k_tree(&cube, 3).unwrap();
+        println!("{:?}", k_tree);
+        assert!(is_k_tree(&k_tree, 3));
+        assert!(contains(&k_tree, &cube));
+    }
+
+    #[test]
+    fn random_partial_k_trees() {
+        let mut state = 0x853c_49e6_748f_ea9b;
+
+        for _ in 0..300 {
+            let k = 1 + random(&mut state, 3);
+            let n = k + 1 + random(&mut state, 20);
+
+            // a random k-tree, on shuffled labels
+            let mut label: Vec<u32> = (0..n as u32).collect();
+            for i in (1..n).rev() {
+                label.swap(i, random(&mut state, i + 1));
+            }
+            let mut cliques = vec![(0..k as u32).collect::<Vec<_>>()];
+            let mut edges: Vec<(u32, u32)> = Vec::new();
+            for v in k as u32..n as u32 {
+                let clique = cliques[random(&mut state, cliques.len())].clone();
+                edges.extend(clique.iter().map(|&w| (v, w)));
+
+                for i in 0..clique.len() {
+                    let mut next = clique.clone();
+                    next[i] = v;
+                    cliques.push(next);
+                }
+            }
+            for a in 0..k as u32 {
+                edges.extend((a + 1..k as u32).map(|b| (a, b)));
+            }
+
+            // and a subgraph of it
+            let kept = edges
+                .into_iter()
+                .filter(|_| random(&mut state, 3) != 0)
+                .map(|(v, w)| (label[v as usize], label[w as usize]));
+            let graph = graph_from_edges(n, kept);
+
+            let k_tree =
+                partial_k_tree(&graph, k).expect("subgraphs of k-trees are partial k-trees");
+            assert!(is_k_tree(&k_tree, k));
+            assert!(contains(&k_tree, &graph));
+        }
+    }
+
+    #[test]
+    fn only_up_to_3_trees() {
+        let path = graph_from_edges(3, vec![(0, 1), (1, 2)]);
+
+        assert!(is_partial_k_tree(&path, 3));
+        assert!(partial_k_tree(&path, 4).is_none());
+    }
+
+    #[test]
+    fn k5_is_not_a_partial_3_tree() {
+        let k5 = graph_from_edges(5, (0..5).flat_map(|a| (a + 1..5).map(move |b| (a, b))));
+
+        assert!(!is_partial_k_tree(&k5, 3));
+    }
+}
@@ -1,4 +1,20 @@
+pub mod chordal;
+pub mod chordal_bipartite;
+pub mod clique_tree;
+pub mod cograph;
 pub mod common;
+pub mod dually_chordal;
+pub mod interval;
+pub mod k_tree;
+pub mod minimal_separators;
 pub mod rayon;
+pub mod search;
 pub mod serial;
+pub mod split;
+pub mod strongly_chordal;
+#[cfg(test)]
+mod test_util;
 pub mod threads;
+pub mod threshold;
+pub mod tree_decomposition;
+pub mod triangulation;
@@ -0,0 +1,238 @@
+use std::collections::{HashSet, VecDeque};
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::clique_tree::CliqueTree;
+
+type Graph = Csr<(), (), Undirected>;
+
+// A minimal separator S: removing it leaves at least two full components, components C with
+// N(C) = S, and it is then a minimal separator of any two vertices in different ones of those
+// The empty set is one exactly when the graph is disconnected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinimalSeparator {
+    // sorted
+    pub separator: Vec<u32>,
+    // each one sorted, and ordered by their smallest vertex
+    pub full_components: Vec<Vec<u32>>,
+}
+
+// Lists the minimal separators of a graph, each one once
+// For a chordal graph, those are the separators of a clique tree, all found upfront in
+// O(n + m), and each one then costs O(n + m) to find its full components
+// Otherwise, we follow Berry, Bordat and Cogis: for every vertex v, the neighbourhood of each
+// component of G - N[v] is a minimal separator, and every other one is the neighbourhood of a
+// component of G - (S ∪ N(x)), for some minimal separator S already found and some x in S
+// Generating those from a separator is O(n(n + m)), which is the delay between two of them
+pub fn minimal_separators(graph: &Graph) -> MinimalSeparators<'_> {
+    match CliqueTree::new(graph) {
+        Ok(tree) => MinimalSeparators::chordal(graph, &tree),
+        Err(_) => MinimalSeparators::general(graph),
+    }
+}
+
+pub struct MinimalSeparators<'a> {
+    graph: &'a Graph,
+    // whether every separator is already pending, so that there is nothing left to generate
+    complete: bool,
+    pending: VecDeque<Vec<u32>>,
+    seen: HashSet<Vec<u32>>,
+}
+
+impl<'a> MinimalSeparators<'a> {
+    fn chordal(graph: &'a Graph, tree: &CliqueTree) -> MinimalSeparators<'a> {
+        let mut separators = MinimalSeparators {
+            graph,
+            complete: true,
+            pending: VecDeque::new(),
+            seen: HashSet::new(),
+        };
+
+        // a clique forest has a root per component
+        if tree.roots().nth(1).is_some() {
+            separators.push(Vec::new());
+        }
+
+        for (_, _, separator) in tree.separators() {
+            separators.push(separator.to_vec());
+        }
+
+        separators
+    }
+
+    fn general(graph: &'a Graph) -> MinimalSeparators<'a> {
+        let n = graph.node_count();
+        let mut separators = MinimalSeparators {
+            graph,
+            complete: false,
+            pending: VecDeque::new(),
+            seen: HashSet::new(),
+        };
+
+        let mut removed = vec![false; n];
+        for v in 0..n as u32 {
+            let closed = || {
+                Some(v)
+                    .into_iter()
+                    .chain(graph.neighbors_slice(v).iter().cloned())
+            };
+
+            closed().for_each(|w| removed[w as usize] = true);
+            for (_, neighbourhood) in components(graph, &removed) {
+                separators.push(neighbourhood);
+            }
+            closed().for_each(|w| removed[w as usize] = false);
+        }
+
+        separators
+    }
+
+    fn push(&mut self, separator: Vec<u32>) {
+        if !self.seen.contains(&separator) {
+            self.seen.insert(separator.clone());
+            self.pending.push_back(separator);
+        }
+    }
+}
+
+impl Iterator for MinimalSeparators<'_> {
+    type Item = MinimalSeparator;
+
+    fn next(&mut self) -> Option<MinimalSeparator> {
+        let separator = self.pending.pop_front()?;
+        let graph = self.graph;
+
+        let mut removed = vec![false; graph.node_count()];
+        separator.iter().for_each(|&v| removed[v as usize] = true);
+
+        let full_components = components(graph, &removed)
+            .into_iter()
+            .filter(|(_, neighbourhood)| neighbourhood.len() == separator.len())
+            .map(|(component, _)| component)
+            .collect();
+
+        if !self.complete {
+            for &x in &separator {
+                let neighbours = graph.neighbors_slice(x);
+
+                neighbours.iter().for_each(|&w| removed[w as usize] = true);
+                for (_, neighbourhood) in components(graph, &removed) {
+                    self.push(neighbourhood);
+                }
+                neighbours
+                    .iter()
+                    .filter(|&w| separator.binary_search(w).is_err())
+                    .for_each(|&w| removed[w as usize] = false);
+            }
+        }
+
+        Some(MinimalSeparator {
+            separator,
+            full_components,
+        })
+    }
+}
+
+// The components of the graph without the removed vertices, each one with its neighbourhood,
+// both sorted, and ordered by their smallest vertex. This is O(n + m) but for the sorting
+fn components(graph: &Graph, removed: &[bool]) -> Vec<(Vec<u32>, Vec<u32>)> {
+    let n = graph.node_count();
+    let mut found = Vec::new();
+
+    let mut visited = removed.to_vec();
+    // the last component each removed vertex was found next to
+    let mut next_to = vec![usize::MAX; n];
+    let mut stack = Vec::new();
+
+    for start in 0..n {
+        if visited[start] {
+            continue;
+        }
+
+        let index = found.len();
+        let mut component = Vec::new();
+        let mut neighbourhood = Vec::new();
+
+        visited[start] = true;
+        stack.push(start as u32);
+        while let Some(v) = stack.pop() {
+            component.push(v);
+
+            for &w in graph.neighbors_slice(v) {
+                if removed[w as usize] {
+                    if next_to[w as usize] != index {
+                        next_to[w as usize] = index;
+                        neighbourhood.push(w);
+                    }
+                } else if !visited[w as usize] {
+                    visited[w as usize] = true;
+                    stack.push(w);
+                }
+            }
+        }
+
+        component.sort();
+        neighbourhood.sort();
+        found.push((component, neighbourhood));
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::graph_from_edges;
+
+    fn sorted(separators: impl Iterator<Item = MinimalSeparator>) -> Vec<MinimalSeparator> {
+        let mut separators: Vec<_> = separators.collect();
+        separators.sort_by(|a, b| a.separator.cmp(&b.separator));
+        separators
+    }
+
+    #[test]
+    fn chordal() {
+        // a path 0 - 1 - 2 - 3, and a triangle hanging off 3 by an edge
+        let graph = graph_from_edges(6, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (3, 5)]);
+        let separators = sorted(minimal_separators(&graph));
+
+        println!("{:?}", separators);
+
+        let found: Vec<_> = separators.iter().map(|s| s.separator.clone()).collect();
+        assert_eq!(found, vec![vec![1], vec![2], vec![3]]);
+        assert_eq!(
+            separators[2].full_components,
+            vec![vec![0, 1, 2], vec![4, 5]]
+        );
+
+        assert_eq!(separators, sorted(MinimalSeparators::general(&graph)));
+    }
+
+    #[test]
+    fn cycle() {
+        // the minimal separators of a C5 are its 5 pairs of non-adjacent vertices
+        let graph = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        let separators = sorted(minimal_separators(&graph));
+
+        let found: Vec<_> = separators.iter().map(|s| s.separator.clone()).collect();
+        assert_eq!(
+            found,
+            vec![vec![0, 2], vec![0, 3], vec![1, 3], vec![1, 4], vec![2, 4]]
+        );
+        assert_eq!(separators[0].full_components, vec![vec![1], vec![3, 4]]);
+    }
+
+    #[test]
+    fn disconnected() {
+        let graph = graph_from_edges(5, vec![(0, 1), (2, 3), (3, 4)]);
+        let separators = sorted(minimal_separators(&graph));
+
+        assert_eq!(separators[0].separator, Vec::<u32>::new());
+        assert_eq!(
+            separators[0].full_components,
+            vec![vec![0, 1], vec![2, 3, 4]]
+        );
+        assert_eq!(separators, sorted(MinimalSeparators::general(&graph)));
+    }
+}
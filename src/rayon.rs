@@ -1,14 +1,13 @@
-use std::cmp::Reverse;
-use std::collections::{BTreeSet, HashSet};
+use std::cmp::Ordering;
 use std::ptr::NonNull;
 
 use petgraph::csr::Csr;
-use petgraph::visit::{EdgeRef, IntoEdgeReferences, NodeIndexable};
 use petgraph::Undirected;
 
 use rayon::prelude::*;
 
-use crate::common::{complete_graph_edge_count, rose_cmp};
+use crate::common::{children_covered, is_scheme, scheme_parent, scheme_positions};
+use crate::search::{search_with, LexBfs, LexDfs, Scan};
 
 type Graph = Csr<(), (), Undirected>;
 
@@ -17,90 +16,86 @@ struct UnsafeMutPtr<T>(NonNull<T>);
 unsafe impl<T> Sync for UnsafeMutPtr<T> {}
 unsafe impl<T> Send for UnsafeMutPtr<T> {}
 
+// Scans the vertices with rayon's parallel iterators
+pub struct Rayon;
+
+impl Scan for Rayon {
+    fn best<C, O>(&mut self, n: usize, candidate: C, order: O) -> Option<usize>
+    where
+        C: Fn(usize) -> bool + Sync,
+        O: Fn(usize, usize) -> Ordering + Sync,
+    {
+        (0..n)
+            .into_par_iter()
+            .filter(|&v| candidate(v))
+            .max_by(|&a, &b| order(a, b))
+    }
+
+    fn update<L, U>(&mut self, labels: &mut [L], vertices: &[u32], update: U)
+    where
+        L: Send,
+        U: Fn(&mut L) + Sync,
+    {
+        let labels_ptr = UnsafeMutPtr(NonNull::new(labels.as_mut_ptr()).unwrap());
+        let update = &update;
+
+        // the vertices are distinct, so no two threads touch the same label
+        vertices.par_iter().for_each(move |&w| unsafe {
+            let ptr = labels_ptr.0.as_ptr();
+            update(&mut *ptr.add(w as usize));
+        });
+    }
+}
+
 // A naive implementation of Rose's LexBFS algorithm
 // Not optimal, as the search for an unnumbered vertex with the largest label is O(n) here
 // Thus, this is O(n²), as opposed to O(n) in Rose's paper
+// Ties are broken in favour of the smallest vertex, so that it agrees with the serial one
 pub fn naive_lex_bfs(graph: &Graph) -> Vec<i32> {
-    let n = graph.node_count();
+    search_with(graph, &LexBfs, None, None, &mut Rayon).order
+}
 
-    // assigning ∅ to all vertices
-    let mut sets: Vec<_> = vec![BTreeSet::new(); n];
-    // initialize α to nothing
-    let mut output = vec![0; n];
-    // helper to check if a vertex is numbered
-    // increases space complexity, but is very fast
-    let mut numbered = vec![false; n];
-
-    for i in (0..n).rev() {
-        // "select" portion of the algorithm
-        // this is the not optimal part, as we have to make a linear search on the sets
-        let max_set = sets
-            .par_iter()
-            .enumerate()
-            .filter(|&(idx, _)| !numbered[idx])
-            .max_by(|&(_, a), &(_, b)| rose_cmp(&a, &b))
-            .expect("output vector was empty");
-
-        let v = max_set.0;
-
-        // α(i) = v
-        output[i] = v as i32;
-        numbered[max_set.0] = true;
-
-        // "update2"
-        // afaik optimal
-        let sets_ptr = UnsafeMutPtr(NonNull::new(sets.as_mut_ptr()).unwrap());
-
-        graph
-            .neighbors_slice(graph.from_index(v))
-            .par_iter()
-            .filter(|&neighbor| !numbered[*neighbor as usize])
-            .for_each(move |w| unsafe {
-                let ptr = sets_ptr.0.as_ptr();
-                (*ptr.add(*w as usize)).insert(Reverse(i));
-            });
-    }
+// The same naive search, but for LexDFS, where the most recent visit weighs the most
+// Ties are broken in the same way. This is O(n²) too
+pub fn naive_lex_dfs(graph: &Graph) -> Vec<i32> {
+    naive_lex_dfs_with(graph, None, None)
+}
 
-    output
+// Naive LexDFS starting from `start`, if given, with ties broken in favour of the vertex that
+// comes first in `ties`, or the smallest one if there is no tie-break ordering, as in
+// serial::lex_dfs_with
+pub fn naive_lex_dfs_with(graph: &Graph, start: Option<u32>, ties: Option<&[i32]>) -> Vec<i32> {
+    search_with(graph, &LexDfs, start, ties, &mut Rayon).order
 }
 
 // Now, we're gonna catch the output from our naive lex-bfs
 // and test if it is a PES (EEP)
+// Tarjan and Yannakakis' test, where the parents are found in parallel, and then every vertex
+// checks the later neighbours of its children against its own marked neighbourhood
+// Each chunk of vertices keeps one array of marks, so this is O(n + m), plus O(n) per thread
 pub fn is_pes(scheme: &[i32], graph: &Graph) -> bool {
-    let mut maybe_clique: HashSet<u32> = HashSet::with_capacity(scheme.len());
-    let mut eliminated_vertices: HashSet<u32> = HashSet::with_capacity(scheme.len());
-    let mut neighborhood = HashSet::with_capacity(scheme.len());
-    let mut edges = Vec::with_capacity(graph.edge_count());
-    for eliminated_vertex in scheme {
-        let eliminated_vertex = *eliminated_vertex as u32;
-        eliminated_vertices.insert(eliminated_vertex);
-
-        neighborhood.extend(graph.neighbors_slice(eliminated_vertex).iter().cloned());
-
-        maybe_clique.clear();
-        maybe_clique.extend(neighborhood.difference(&eliminated_vertices));
-
-        neighborhood.clear();
-
-        // Dividing by two here because petgraph counts inbound and outbound vertices
-        edges.clear();
-        edges.extend(graph.edge_references());
-        let subgraph_edge_count = edges
-            .par_iter()
-            .filter(|x| {
-                maybe_clique.contains(&(x.source())) && maybe_clique.contains(&(x.target()))
-            })
-            .count()
-            / 2;
-
-        if subgraph_edge_count != complete_graph_edge_count(maybe_clique.len()) {
-            return false;
-        }
+    let n = graph.node_count();
+    if !is_scheme(scheme, n) {
+        return false;
     }
 
-    true
+    let position = scheme_positions(scheme);
+    let parents: Vec<_> = (0..n as u32)
+        .into_par_iter()
+        .map(|v| scheme_parent(graph, &position, v))
+        .collect();
+
+    let chunk_size = n / ::rayon::current_num_threads() + 1;
+    scheme.par_chunks(chunk_size).all(|chunk| {
+        let mut marked = vec![usize::MAX; n];
+
+        chunk
+            .iter()
+            .all(|&p| children_covered(graph, &position, &parents, p as u32, &mut marked))
+    })
 }
 
+#[allow(clippy::needless_borrow)]
 pub fn is_chordal(graph: &Graph) -> bool {
     let scheme = naive_lex_bfs(&graph);
 
@@ -108,9 +103,11 @@ pub fn is_chordal(graph: &Graph) -> bool {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
-    use crate::common::graph_from_reader;
+    use crate::common::{graph_from_edges, graph_from_reader};
+    use crate::serial::{is_lex_dfs, lex_bfs, lex_dfs, lex_dfs_with};
     use std::fs::File;
     use std::io::BufReader;
     #[test]
@@ -218,6 +215,56 @@ mod tests {
         assert_eq!(is_pes(&res, &graph), false);
     }
 
+    #[test]
+    fn bad_schemes_rayon() {
+        // a path 0 - 1 - 2
+        let graph = graph_from_edges(3, vec![(0, 1), (1, 2)]);
+
+        assert!(is_pes(&[0, 1, 2], &graph));
+        assert!(!is_pes(&[1, 0, 2], &graph));
+        assert!(!is_pes(&[0, 1], &graph));
+        assert!(!is_pes(&[0, 0, 2], &graph));
+    }
+
+    #[test]
+    fn lex_dfs_start_and_ties_rayon() {
+        // a C4 0 - 1 - 2 - 3 with the chord 1 - 3, and a pendant 4 on 0, so most steps are ties
+        let graph = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 0), (1, 3), (0, 4)]);
+        let orders: [&[i32]; 3] = [&[4, 3, 2, 1, 0], &[2, 0, 4, 1, 3], &[1, 4, 3, 0, 2]];
+
+        for start in (0..5).map(Some).chain(Some(None)) {
+            for ties in orders.iter().cloned().map(Some).chain(Some(None)) {
+                let res = naive_lex_dfs_with(&graph, start, ties);
+
+                assert_eq!(res, lex_dfs_with(&graph, start, ties));
+                assert!(is_lex_dfs(&res, &graph));
+            }
+        }
+    }
+
+    #[test]
+    fn same_ties_as_serial() {
+        // every vertex but the middle one is tied at some point
+        let graph = graph_from_edges(5, vec![(0, 2), (1, 2), (2, 3), (2, 4), (3, 4)]);
+
+        assert_eq!(naive_lex_bfs(&graph), lex_bfs(&graph));
+    }
+
+    #[test]
+    fn lex_dfs_rayon() {
+        let graph = graph_from_edges(
+            6,
+            vec![(0, 1), (0, 2), (1, 3), (2, 4), (3, 5), (4, 5), (1, 2)],
+        );
+
+        let res = naive_lex_dfs(&graph);
+
+        println!("{:?}", res);
+
+        assert_eq!(res, lex_dfs(&graph));
+        assert!(is_lex_dfs(&res, &graph));
+    }
+
     #[test]
     fn from_file_rayon() {
         let file = File::open("k100.txt").unwrap();
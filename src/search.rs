@@ -0,0 +1,532 @@
+use std::cmp::Ordering;
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::common::scheme_positions;
+
+type Graph = Csr<(), (), Undirected>;
+
+// Corneil and Krueger's generic search: repeatedly visit an unvisited vertex, picked by what
+// it knows of its visited neighbours. A rule says what that is, as a label updated whenever
+// a neighbour is visited, and which labels win
+// Rules and labels are shared with the threads of the parallel searches
+pub trait SearchRule: Sync {
+    type Label: Clone + Send + Sync;
+
+    // the label of a vertex with no visited neighbours
+    fn empty(&self) -> Self::Label;
+
+    // a neighbour of the vertex was just visited, as the step-th vertex, counting from 0
+    fn update(&self, label: &mut Self::Label, step: usize);
+
+    // Greater when a vertex labelled a must be visited before one labelled b, and None when
+    // the rule allows either, which lets it be a partial order, as MNS needs
+    fn compare(&self, a: &Self::Label, b: &Self::Label) -> Option<Ordering>;
+
+    // which visited neighbour of a vertex is its parent in the search tree
+    fn parent(&self) -> Parent {
+        Parent::First
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parent {
+    First,
+    Last,
+}
+
+// The vertices in the order a search visited them, which, like every other search here,
+// is backwards, so that the first vertex visited is the last one
+// Each vertex has its parent in the search tree, None for the first one of each component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOrder {
+    pub order: Vec<i32>,
+    pub parents: Vec<Option<u32>>,
+}
+
+impl SearchOrder {
+    // The edges of the search tree, each vertex with its parent
+    pub fn tree_edges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.parents
+            .iter()
+            .enumerate()
+            .filter_map(|(v, parent)| parent.map(|parent| (v as u32, parent)))
+    }
+}
+
+// How a search goes over the vertices: one after the other, or split between threads
+pub trait Scan {
+    // The best candidate among 0..n, a beating b when order(a, b) is Greater, or None if there
+    // are no candidates. order need not be a total order, but only then is this the maximum
+    fn best<C, O>(&mut self, n: usize, candidate: C, order: O) -> Option<usize>
+    where
+        C: Fn(usize) -> bool + Sync,
+        O: Fn(usize, usize) -> Ordering + Sync;
+
+    // Updates the labels of the given vertices, which are all distinct
+    fn update<L, U>(&mut self, labels: &mut [L], vertices: &[u32], update: U)
+    where
+        L: Send,
+        U: Fn(&mut L) + Sync;
+}
+
+// The scan of a single thread
+pub struct Sequential;
+
+impl Scan for Sequential {
+    fn best<C, O>(&mut self, n: usize, candidate: C, order: O) -> Option<usize>
+    where
+        C: Fn(usize) -> bool + Sync,
+        O: Fn(usize, usize) -> Ordering + Sync,
+    {
+        (0..n)
+            .filter(|&v| candidate(v))
+            .max_by(|&a, &b| order(a, b))
+    }
+
+    fn update<L, U>(&mut self, labels: &mut [L], vertices: &[u32], update: U)
+    where
+        L: Send,
+        U: Fn(&mut L) + Sync,
+    {
+        for &v in vertices {
+            update(&mut labels[v as usize]);
+        }
+    }
+}
+
+// Runs a search, visiting at each step a vertex whose label no other one beats, with ties
+// going to the smallest vertex
+pub fn search<R: SearchRule>(graph: &Graph, rule: &R) -> SearchOrder {
+    search_with(graph, rule, None, None, &mut Sequential)
+}
+
+// The same search, starting from `start`, if given, with ties broken in favour of the vertex
+// that comes first in `ties`, or the smallest one if there is no tie-break ordering
+// We scan the unvisited vertices for the best one, taking incomparable labels as ties, and
+// then for the best one of those that beat it, until there are none. A total order needs
+// two scans, and any order at most one for each step up the chain of labels we climb
+// This is O(n²) label comparisons for total orders, and O(n³) at worst for partial ones,
+// on top of the m label updates
+pub fn search_with<R: SearchRule, S: Scan>(
+    graph: &Graph,
+    rule: &R,
+    start: Option<u32>,
+    ties: Option<&[i32]>,
+    scan: &mut S,
+) -> SearchOrder {
+    let n = graph.node_count();
+    let rank = match ties {
+        Some(ties) => scheme_positions(ties),
+        None => (0..n).collect(),
+    };
+
+    let mut labels = vec![rule.empty(); n];
+    let mut visited = vec![false; n];
+    let mut parents = vec![None; n];
+    let mut order = vec![0; n];
+
+    for step in 0..n {
+        let v = match start {
+            Some(start) if step == 0 => start as usize,
+            _ => {
+                let (labels, visited, rank) = (&labels, &visited, &rank);
+                let unvisited = |v: usize| !visited[v];
+                let tie_broken = |a: usize, b: usize| {
+                    rule.compare(&labels[a], &labels[b])
+                        .unwrap_or(Ordering::Equal)
+                        .then(rank[b].cmp(&rank[a]))
+                };
+
+                let mut choice = scan.best(n, unvisited, tie_broken).unwrap();
+                loop {
+                    let beats = |v: usize| {
+                        !visited[v]
+                            && rule.compare(&labels[v], &labels[choice]) == Some(Ordering::Greater)
+                    };
+
+                    match scan.best(n, beats, tie_broken) {
+                        Some(v) => choice = v,
+                        None => break choice,
+                    }
+                }
+            }
+        };
+
+        visited[v] = true;
+        order[n - 1 - step] = v as i32;
+
+        let unvisited: Vec<u32> = graph
+            .neighbors_slice(v as u32)
+            .iter()
+            .cloned()
+            .filter(|&w| !visited[w as usize])
+            .collect();
+
+        scan.update(&mut labels, &unvisited, |label| rule.update(label, step));
+        for &w in &unvisited {
+            if parents[w as usize].is_none() || rule.parent() == Parent::Last {
+                parents[w as usize] = Some(v as u32);
+            }
+        }
+    }
+
+    SearchOrder { order, parents }
+}
+
+// Breadth first search: the vertex whose first visited neighbour came earliest
+pub struct Bfs;
+
+impl SearchRule for Bfs {
+    type Label = Option<usize>;
+
+    fn empty(&self) -> Option<usize> {
+        None
+    }
+
+    fn update(&self, label: &mut Option<usize>, step: usize) {
+        label.get_or_insert(step);
+    }
+
+    fn compare(&self, a: &Option<usize>, b: &Option<usize>) -> Option<Ordering> {
+        Some(match (a, b) {
+            (Some(a), Some(b)) => b.cmp(a),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        })
+    }
+}
+
+// Depth first search: the vertex whose last visited neighbour came latest
+pub struct Dfs;
+
+impl SearchRule for Dfs {
+    type Label = Option<usize>;
+
+    fn empty(&self) -> Option<usize> {
+        None
+    }
+
+    fn update(&self, label: &mut Option<usize>, step: usize) {
+        *label = Some(step);
+    }
+
+    fn compare(&self, a: &Option<usize>, b: &Option<usize>) -> Option<Ordering> {
+        Some(a.cmp(b))
+    }
+
+    fn parent(&self) -> Parent {
+        Parent::Last
+    }
+}
+
+// Rose, Tarjan and Lueker's LexBFS: the labels are the visited neighbours, in visit order,
+// compared lexicographically, where an earlier visit wins
+pub struct LexBfs;
+
+impl SearchRule for LexBfs {
+    type Label = Vec<usize>;
+
+    fn empty(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn update(&self, label: &mut Vec<usize>, step: usize) {
+        label.push(step);
+    }
+
+    fn compare(&self, a: &Vec<usize>, b: &Vec<usize>) -> Option<Ordering> {
+        Some(lexicographic(
+            a.iter().copied(),
+            b.iter().copied(),
+            |x, y| y.cmp(&x),
+        ))
+    }
+}
+
+// LexDFS: the same labels as LexBFS, compared from the most recent visit, which wins
+pub struct LexDfs;
+
+impl SearchRule for LexDfs {
+    type Label = Vec<usize>;
+
+    fn empty(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn update(&self, label: &mut Vec<usize>, step: usize) {
+        label.push(step);
+    }
+
+    fn compare(&self, a: &Vec<usize>, b: &Vec<usize>) -> Option<Ordering> {
+        Some(lexicographic(
+            a.iter().rev().copied(),
+            b.iter().rev().copied(),
+            |x, y| x.cmp(&y),
+        ))
+    }
+
+    fn parent(&self) -> Parent {
+        Parent::Last
+    }
+}
+
+// Tarjan and Yannakakis' Maximum Cardinality Search: the most visited neighbours wins
+// Its parents, like those of MNS, are the last visited neighbours, as in a PES
+pub struct Mcs;
+
+impl SearchRule for Mcs {
+    type Label = usize;
+
+    fn empty(&self) -> usize {
+        0
+    }
+
+    fn update(&self, label: &mut usize, _: usize) {
+        *label += 1;
+    }
+
+    fn compare(&self, a: &usize, b: &usize) -> Option<Ordering> {
+        Some(a.cmp(b))
+    }
+
+    fn parent(&self) -> Parent {
+        Parent::Last
+    }
+}
+
+// Maximal Neighbourhood Search: any vertex whose set of visited neighbours isn't strictly
+// contained in the set of another one. LexBFS, LexDFS and MCS orders are all MNS orders
+pub struct Mns;
+
+impl SearchRule for Mns {
+    // the steps of the visited neighbours, sorted as they only ever grow
+    type Label = Vec<usize>;
+
+    fn empty(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn update(&self, label: &mut Vec<usize>, step: usize) {
+        label.push(step);
+    }
+
+    fn compare(&self, a: &Vec<usize>, b: &Vec<usize>) -> Option<Ordering> {
+        let contains = |a: &[usize], b: &[usize]| b.iter().all(|x| a.binary_search(x).is_ok());
+
+        match (contains(a, b), contains(b, a)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Greater),
+            (false, true) => Some(Ordering::Less),
+            (false, false) => None,
+        }
+    }
+
+    fn parent(&self) -> Parent {
+        Parent::Last
+    }
+}
+
+// Compares two sequences element by element, with a sequence that is a prefix of the other
+// one losing to it
+fn lexicographic(
+    a: impl Iterator<Item = usize>,
+    b: impl Iterator<Item = usize>,
+    element: impl Fn(usize, usize) -> Ordering,
+) -> Ordering {
+    let (mut a, mut b) = (a.fuse(), b.fuse());
+
+    loop {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => match element(x, y) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            },
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (None, None) => return Ordering::Equal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::graph_from_edges;
+    use crate::rayon::Rayon;
+    use crate::serial::{is_lex_dfs, is_pes, lex_bfs, lex_bfs_plus, lex_dfs, lex_dfs_with};
+    use crate::test_util::random;
+    use crate::threads::Threads;
+    use scoped_threadpool::Pool;
+
+    // a C6 with a pendant on 0 and a chord 1 - 5
+    fn graph() -> Graph {
+        graph_from_edges(
+            7,
+            vec![
+                (0, 1),
+                (1, 2),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 0),
+                (0, 6),
+                (1, 5),
+            ],
+        )
+    }
+
+    fn visits(found: &SearchOrder) -> Vec<i32> {
+        found.order.iter().rev().cloned().collect()
+    }
+
+    fn assert_tree(graph: &Graph, found: &SearchOrder) {
+        assert_eq!(found.tree_edges().count(), graph.node_count() - 1);
+        for (v, parent) in found.tree_edges() {
+            assert!(graph.contains_edge(v, parent));
+        }
+    }
+
+    #[test]
+    fn breadth_and_depth_first() {
+        let graph = graph();
+
+        let bfs = search(&graph, &Bfs);
+        assert_eq!(visits(&bfs), vec![0, 1, 5, 6, 2, 4, 3]);
+        assert_eq!(bfs.parents[3], Some(2));
+        assert_tree(&graph, &bfs);
+
+        let dfs = search(&graph, &Dfs);
+        assert_eq!(visits(&dfs), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(dfs.parents[5], Some(4));
+        assert_tree(&graph, &dfs);
+    }
+
+    #[test]
+    fn lexicographic_searches() {
+        let graph = graph();
+
+        let lex_bfs_order = search(&graph, &LexBfs);
+        assert_eq!(lex_bfs_order.order, lex_bfs(&graph));
+        assert_tree(&graph, &lex_bfs_order);
+
+        let lex_dfs_order = search(&graph, &LexDfs);
+        assert_eq!(lex_dfs_order.order, lex_dfs(&graph));
+        assert!(is_lex_dfs(&lex_dfs_order.order, &graph));
+    }
+
+    #[test]
+    fn chordal_searches() {
+        // two triangles sharing an edge, with a pendant
+        let graph = graph_from_edges(5, vec![(0, 1), (1, 2), (0, 2), (1, 3), (2, 3), (3, 4)]);
+
+        for found in &[search(&graph, &Mcs), search(&graph, &Mns)] {
+            println!("{:?}", found);
+            assert!(is_pes(&found.order, &graph));
+            assert_tree(&graph, found);
+        }
+    }
+
+    #[test]
+    fn mns_is_a_partial_order() {
+        assert_eq!(
+            Mns.compare(&vec![0, 2], &vec![0, 1, 2]),
+            Some(Ordering::Less)
+        );
+        assert_eq!(Mns.compare(&vec![0, 2], &vec![1]), None);
+        assert_eq!(
+            LexBfs.compare(&vec![0, 2], &vec![1]),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            LexDfs.compare(&vec![0, 2], &vec![1]),
+            Some(Ordering::Greater)
+        );
+    }
+
+    fn random_order(state: &mut u64, n: usize) -> Vec<i32> {
+        let mut order: Vec<i32> = (0..n as i32).collect();
+        for i in (1..n).rev() {
+            order.swap(i, random(state, i + 1));
+        }
+
+        order
+    }
+
+    #[test]
+    fn random_searches_agree() {
+        let mut state = 0x2545_f491_4f6c_dd1d;
+        let mut pool = Pool::new(3);
+
+        for _ in 0..300 {
+            let n = 1 + random(&mut state, 12);
+            let density = 1 + random(&mut state, 9);
+            let edges: Vec<_> = (0..n as u32)
+                .flat_map(|v| (v + 1..n as u32).map(move |w| (v, w)))
+                .filter(|_| random(&mut state, 10) < density)
+                .collect();
+            let graph = graph_from_edges(n, edges);
+
+            let start = Some(random(&mut state, n) as u32);
+            let prev = random_order(&mut state, n);
+            let ties = Some(&prev[..]);
+
+            // every way of scanning finds the same order, ties included
+            let lex_bfs_order = search(&graph, &LexBfs).order;
+            assert_eq!(lex_bfs_order, lex_bfs(&graph));
+            assert_eq!(
+                search_with(&graph, &LexBfs, None, None, &mut Rayon).order,
+                lex_bfs_order
+            );
+            assert_eq!(
+                search_with(&graph, &LexBfs, None, None, &mut Threads(&mut pool)).order,
+                lex_bfs_order
+            );
+
+            // LexBFS+ breaks ties by the previous scheme
+            let plus = lex_bfs_plus(&graph, &prev);
+            assert_eq!(
+                search_with(&graph, &LexBfs, None, ties, &mut Sequential).order,
+                plus
+            );
+            assert_eq!(
+                search_with(&graph, &LexBfs, None, ties, &mut Rayon).order,
+                plus
+            );
+            assert_eq!(
+                search_with(&graph, &LexBfs, None, ties, &mut Threads(&mut pool)).order,
+                plus
+            );
+
+            for &(start, ties) in &[(None, None), (start, None), (None, ties), (start, ties)] {
+                let lex_dfs_order = lex_dfs_with(&graph, start, ties);
+                assert_eq!(
+                    search_with(&graph, &LexDfs, start, ties, &mut Sequential).order,
+                    lex_dfs_order
+                );
+                assert_eq!(
+                    search_with(&graph, &LexDfs, start, ties, &mut Rayon).order,
+                    lex_dfs_order
+                );
+                assert_eq!(
+                    search_with(&graph, &LexDfs, start, ties, &mut Threads(&mut pool)).order,
+                    lex_dfs_order
+                );
+
+                let bfs = search_with(&graph, &Bfs, start, ties, &mut Sequential);
+                assert_eq!(search_with(&graph, &Bfs, start, ties, &mut Rayon), bfs);
+                assert_eq!(
+                    search_with(&graph, &Bfs, start, ties, &mut Threads(&mut pool)),
+                    bfs
+                );
+
+                let mcs = search_with(&graph, &Mcs, start, ties, &mut Sequential);
+                assert_eq!(search_with(&graph, &Mcs, start, ties, &mut Rayon), mcs);
+                assert_eq!(
+                    search_with(&graph, &Mcs, start, ties, &mut Threads(&mut pool)),
+                    mcs
+                );
+            }
+        }
+    }
+}
@@ -1,130 +1,797 @@
-use std::cmp::Reverse;
-use std::collections::BTreeSet;
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
 
 use petgraph::csr::Csr;
-use petgraph::visit::EdgeRef;
-use petgraph::visit::IntoEdgeReferences;
 use petgraph::Undirected;
 
-use crate::common::{complete_graph_edge_count, rose_cmp};
+use crate::common::{is_scheme, scheme_positions, NotChordal};
+use crate::search::{search, LexBfs};
 
 type Graph = Csr<(), (), Undirected>;
 
-// unfinished. Habib's algorithm
-/*
-pub fn lex_bfs(graph: &Graph) -> Vec<NodeIndex<u32>> {
-    let mut sets: VecDeque<_> = iter::once(graph.node_indices().collect::<IndexSet<_>>()).collect();
-    let mut output = Vec::with_capacity(graph.node_count());
+// Sentinel for the intrusive linked lists used by `Partition`
+const NIL: usize = usize::MAX;
 
-    while !sets.is_empty() {
-        let set = &mut sets[0];
-        let v = set.pop().expect("Failed to pop element from set");
+// A class of the ordered partition refined by `lex_bfs`
+struct Class {
+    head: usize,
+    tail: usize,
+    prev: usize,
+    next: usize,
+    // the class split off from this one during the current refinement step, if any
+    split: usize,
+    split_step: usize,
+    // classes with smaller ranks come first, kept only by `split_to_front`
+    rank: isize,
+}
+
+// An ordered partition of the unnumbered vertices
+// Each class is a doubly linked list of vertices, and the classes themselves
+// form a doubly linked list, ordered from the largest label to the smallest one
+struct Partition {
+    classes: Vec<Class>,
+    first: usize,
+    // the rank for the next class moved to the front
+    front_rank: isize,
+    class_of: Vec<usize>,
+    prev: Vec<usize>,
+    next: Vec<usize>,
+}
+
+impl Partition {
+    // A single class holding every vertex, in the given order
+    fn new(order: impl Iterator<Item = usize>, n: usize) -> Partition {
+        let mut partition = Partition {
+            classes: Vec::with_capacity(n),
+            first: 0,
+            front_rank: -1,
+            class_of: vec![0; n],
+            prev: vec![NIL; n],
+            next: vec![NIL; n],
+        };
 
-        if set.is_empty() {
-            sets.pop_front().unwrap(); // this should never fail
+        partition.classes.push(Class {
+            head: NIL,
+            tail: NIL,
+            prev: NIL,
+            next: NIL,
+            split: NIL,
+            split_step: NIL,
+            rank: 0,
+        });
+
+        for v in order {
+            partition.append(0, v);
         }
 
-        // yield v
-        output.push(v);
+        partition
+    }
+
+    fn first_vertex(&self) -> usize {
+        self.classes[self.first].head
+    }
+
+    // Whether v is in the first class, which a removed vertex never is
+    fn is_tied(&self, v: usize) -> bool {
+        self.class_of[v] == self.first
+    }
 
-        let neighborhood: IndexSet<_> = graph.neighbors(v).collect();
+    fn append(&mut self, class: usize, v: usize) {
+        let tail = self.classes[class].tail;
+        self.prev[v] = tail;
+        self.next[v] = NIL;
 
-        for set in &sets {
-            let _diff = set.difference(&neighborhood);
-            let _intersection = set.intersection(&neighborhood);
+        if tail == NIL {
+            self.classes[class].head = v;
+        } else {
+            self.next[tail] = v;
         }
+
+        self.classes[class].tail = v;
+        self.class_of[v] = class;
     }
 
-    output
+    fn prepend(&mut self, class: usize, v: usize) {
+        let head = self.classes[class].head;
+        self.prev[v] = NIL;
+        self.next[v] = head;
+
+        if head == NIL {
+            self.classes[class].tail = v;
+        } else {
+            self.prev[head] = v;
+        }
+
+        self.classes[class].head = v;
+        self.class_of[v] = class;
+    }
+
+    // Removes v from its class, dropping the class if it becomes empty
+    fn remove(&mut self, v: usize) {
+        let class = self.class_of[v];
+        let (before, after) = (self.prev[v], self.next[v]);
+
+        if before == NIL {
+            self.classes[class].head = after;
+        } else {
+            self.next[before] = after;
+        }
+
+        if after == NIL {
+            self.classes[class].tail = before;
+        } else {
+            self.prev[after] = before;
+        }
+
+        if self.classes[class].head == NIL {
+            let (before, after) = (self.classes[class].prev, self.classes[class].next);
+
+            if before == NIL {
+                self.first = after;
+            } else {
+                self.classes[before].next = after;
+            }
+
+            if after != NIL {
+                self.classes[after].prev = before;
+            }
+        }
+
+        self.class_of[v] = NIL;
+    }
+
+    // Moves v to the class split off from its own during `step`, placed right before it
+    // Vertices keep their relative order, both in the old class and in the new one
+    fn split_off(&mut self, v: usize, step: usize) {
+        let old = self.class_of[v];
+
+        if self.classes[old].split_step != step {
+            let new = self.classes.len();
+            let before = self.classes[old].prev;
+            self.classes.push(Class {
+                head: NIL,
+                tail: NIL,
+                prev: before,
+                next: old,
+                split: NIL,
+                split_step: NIL,
+                rank: self.classes[old].rank,
+            });
+
+            if before == NIL {
+                self.first = new;
+            } else {
+                self.classes[before].next = new;
+            }
+
+            self.classes[old].prev = new;
+            self.classes[old].split = new;
+            self.classes[old].split_step = step;
+        }
+
+        let new = self.classes[old].split;
+        self.remove(v);
+        self.append(new, v);
+    }
+
+    // Like `split_off`, but with the new class placed right after the old one
+    fn split_off_behind(&mut self, v: usize, step: usize) {
+        let old = self.class_of[v];
+
+        if self.classes[old].split_step != step {
+            let new = self.classes.len();
+            let after = self.classes[old].next;
+            self.classes.push(Class {
+                head: NIL,
+                tail: NIL,
+                prev: old,
+                next: after,
+                split: NIL,
+                split_step: NIL,
+                rank: self.classes[old].rank,
+            });
+
+            if after != NIL {
+                self.classes[after].prev = new;
+            }
+
+            self.classes[old].next = new;
+            self.classes[old].split = new;
+            self.classes[old].split_step = step;
+        }
+
+        let new = self.classes[old].split;
+        self.remove(v);
+        self.append(new, v);
+    }
+
+    // Moves v to the class split off from its own during `step`, placed at the very front
+    // Moving the vertices from the last one to the first keeps their relative order, both
+    // among the classes split off and inside each of them
+    fn split_to_front(&mut self, v: usize, step: usize) {
+        let old = self.class_of[v];
+
+        if self.classes[old].split_step != step {
+            let new = self.classes.len();
+            let first = self.first;
+            self.classes.push(Class {
+                head: NIL,
+                tail: NIL,
+                prev: NIL,
+                next: first,
+                split: NIL,
+                split_step: NIL,
+                rank: self.front_rank,
+            });
+            self.front_rank -= 1;
+
+            if first != NIL {
+                self.classes[first].prev = new;
+            }
+            self.first = new;
+
+            self.classes[old].split = new;
+            self.classes[old].split_step = step;
+        }
+
+        let new = self.classes[old].split;
+        self.remove(v);
+        self.prepend(new, v);
+    }
 }
-*/
 
-// A naive implementation of Rose's LexBFS algorithm
-// Not optimal, as the search for an unnumbered vertex with the largest label is O(n) here
-// Thus, this is O(n²), as opposed to O(n) in Rose's paper
-pub fn naive_lex_bfs(graph: &Graph) -> Vec<i32> {
+// Habib, McConnell, Paul and Viennot's partition refinement LexBFS
+// Instead of storing labels, we keep the unnumbered vertices in an ordered partition
+// Vertices in the same class have the same label, and the first class holds the largest one
+// Numbering a vertex splits every class it has neighbours in, moving those neighbours
+// into a new class placed right before the old one. This is O(n + m)
+// Ties are broken in favour of the smallest vertex
+pub fn lex_bfs(graph: &Graph) -> Vec<i32> {
     let n = graph.node_count();
 
+    // neighbour slices are sorted, so splitting keeps every class sorted
+    refine_lex_bfs(
+        n,
+        0..n,
+        |v| graph.neighbors_slice(v as u32),
+        Partition::first_vertex,
+        Partition::split_off,
+    )
+}
+
+// LexBFS+: ties are broken in favour of the vertex visited last by the previous search,
+// that is, the one eliminated first by the `prev` scheme. This is O(n + m)
+pub fn lex_bfs_plus(graph: &Graph, prev: &[i32]) -> Vec<i32> {
+    let n = graph.node_count();
+
+    // neighbours sorted by their position in prev, so that splitting keeps classes in that order
+    let mut neighbours = vec![Vec::new(); n];
+    for &w in prev {
+        for &v in graph.neighbors_slice(w as u32) {
+            neighbours[v as usize].push(w as u32);
+        }
+    }
+
+    refine_lex_bfs(
+        n,
+        prev.iter().map(|&v| v as usize),
+        |v| &neighbours[v],
+        Partition::first_vertex,
+        Partition::split_off,
+    )
+}
+
+// LexBFS of the complement of G, without building it, starting from the first vertex of
+// `order` and breaking ties in favour of the vertex that comes first in it
+// Numbering a vertex raises the labels of its non-neighbours, so it is its neighbours that
+// leave their classes, for new ones placed right after the old ones. This is O(n + m)
+pub fn complement_lex_bfs(graph: &Graph, order: &[i32]) -> Vec<i32> {
+    let n = graph.node_count();
+
+    let mut neighbours = vec![Vec::new(); n];
+    for &w in order {
+        for &v in graph.neighbors_slice(w as u32) {
+            neighbours[v as usize].push(w as u32);
+        }
+    }
+
+    refine_lex_bfs(
+        n,
+        order.iter().map(|&v| v as usize),
+        |v| &neighbours[v],
+        Partition::first_vertex,
+        Partition::split_off_behind,
+    )
+}
+
+// Corneil, Olariu and Stewart's LexBFS*, which breaks ties using two previous sweeps
+// Among the vertices with the largest label, let α be the one visited last by `a`, and β the
+// one visited last by `b`. We pick β if its neighbours among them, other than α, are strictly
+// fewer than those of α, other than β, and contained in them, and α otherwise
+// We refine two copies of the partition alike, one kept in the order of `a` and the other in
+// the order of `b`, so that α and β are the heads of their first classes. Comparing them
+// against the marked neighbours of α costs both their degrees, so this is O(n + m) plus the
+// degree of the vertex that isn't picked at each step, O(nΔ + m) at worst
+pub fn lex_bfs_star(graph: &Graph, a: &[i32], b: &[i32]) -> Vec<i32> {
+    let n = graph.node_count();
+
+    // neighbours sorted by their position in each sweep, so that splitting keeps that order
+    let sorted_by = |order: &[i32]| {
+        let mut neighbours = vec![Vec::new(); n];
+        for &w in order {
+            for &v in graph.neighbors_slice(w as u32) {
+                neighbours[v as usize].push(w as u32);
+            }
+        }
+        neighbours
+    };
+    let (a_neighbours, b_neighbours) = (sorted_by(a), sorted_by(b));
+
+    let mut in_a = Partition::new(a.iter().map(|&v| v as usize), n);
+    let mut in_b = Partition::new(b.iter().map(|&v| v as usize), n);
+    let mut output = vec![0; n];
+    let mut numbered = vec![false; n];
+    // marked[w] == v for the neighbours w of the last α compared, v
+    let mut marked = vec![NIL; n];
+
+    for i in (0..n).rev() {
+        // both partitions have the same classes, so these are tied
+        let (alpha, beta) = (in_a.first_vertex(), in_b.first_vertex());
+
+        let v = if alpha != beta {
+            for &w in graph.neighbors_slice(alpha as u32) {
+                marked[w as usize] = alpha;
+            }
+
+            let tied = |v: usize, other: usize| {
+                let in_a = &in_a;
+                graph
+                    .neighbors_slice(v as u32)
+                    .iter()
+                    .map(|&w| w as usize)
+                    .filter(move |&w| w != other && in_a.is_tied(w))
+            };
+
+            let fewer = tied(beta, alpha).count() < tied(alpha, beta).count();
+            if fewer && tied(beta, alpha).all(|w| marked[w] == alpha) {
+                beta
+            } else {
+                alpha
+            }
+        } else {
+            alpha
+        };
+
+        // α(i) = v
+        output[i] = v as i32;
+        numbered[v] = true;
+        in_a.remove(v);
+        in_b.remove(v);
+
+        for &w in &a_neighbours[v] {
+            if !numbered[w as usize] {
+                in_a.split_off(w as usize, i);
+            }
+        }
+        for &w in &b_neighbours[v] {
+            if !numbered[w as usize] {
+                in_b.split_off(w as usize, i);
+            }
+        }
+    }
+
+    output
+}
+
+// A sweep of a multi-sweep algorithm, breaking ties by the sweeps before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sweep {
+    LexBfs,
+    // by the previous sweep, or as LexBFS for the first one
+    LexBfsPlus,
+    // by the two previous sweeps, the latest one as `b`, or as LexBFS+ with fewer than two
+    LexBfsStar,
+}
+
+// A sequence of sweeps, as in Corneil, Olariu and Stewart's multi-sweep recognition algorithms
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiSweep {
+    pub sweeps: Vec<Sweep>,
+}
+
+impl MultiSweep {
+    pub fn new(sweeps: Vec<Sweep>) -> MultiSweep {
+        MultiSweep { sweeps }
+    }
+
+    // Runs every sweep in turn, returning each ordering as a scheme
+    pub fn run(&self, graph: &Graph) -> Vec<Vec<i32>> {
+        let mut orderings: Vec<Vec<i32>> = Vec::with_capacity(self.sweeps.len());
+
+        for &sweep in &self.sweeps {
+            let ordering = match (sweep, orderings.as_slice()) {
+                (Sweep::LexBfsStar, [.., a, b]) => lex_bfs_star(graph, a, b),
+                (Sweep::LexBfsStar, [.., prev]) | (Sweep::LexBfsPlus, [.., prev]) => {
+                    lex_bfs_plus(graph, prev)
+                }
+                _ => lex_bfs(graph),
+            };
+
+            orderings.push(ordering);
+        }
+
+        orderings
+    }
+
+    // The ordering of the last sweep, or None if there are no sweeps
+    pub fn last(&self, graph: &Graph) -> Option<Vec<i32>> {
+        self.run(graph).pop()
+    }
+}
+
+// Each class is kept in the order of `initial`, as long as `neighbours` follows it too
+// `select` picks a vertex from the first class, whose vertices have the largest label, and
+// `split` moves a neighbour of the vertex just numbered out of its class
+fn refine_lex_bfs<'a>(
+    n: usize,
+    initial: impl Iterator<Item = usize>,
+    neighbours: impl Fn(usize) -> &'a [u32],
+    select: impl Fn(&Partition) -> usize,
+    split: impl Fn(&mut Partition, usize, usize),
+) -> Vec<i32> {
     // assigning ∅ to all vertices
-    let mut sets: Vec<_> = vec![BTreeSet::new(); n]; //graph.node_indices().map(|_| BTreeSet::new()).collect();
-                                                     // initialize α to nothing
+    let mut partition = Partition::new(initial, n);
     let mut output = vec![0; n];
-    // helper to check if a vertex is numbered
-    // increases space complexity, but is very fast
     let mut numbered = vec![false; n];
 
     for i in (0..n).rev() {
-        // "select" portion of the algorithm
-        // this is the not optimal part, as we have to make a linear search on the sets
-        let max_set = sets
-            .iter()
-            .enumerate()
-            .filter(|&(idx, _)| !numbered[idx])
-            .max_by(|&(_, a), &(_, b)| rose_cmp(&a, &b))
-            .expect("output vector was empty");
+        // "select": a vertex of the first class, which has the largest label
+        let v = select(&partition);
 
         // α(i) = v
-        output[i] = max_set.0 as i32;
-        numbered[max_set.0] = true;
+        output[i] = v as i32;
+        numbered[v] = true;
+        partition.remove(v);
 
-        // "update2"
-        // afaik optimal
-        graph
-            .neighbors_slice(max_set.0 as u32)
+        // "update": split the classes of the unnumbered neighbours of v
+        for &w in neighbours(v) {
+            if !numbered[w as usize] {
+                split(&mut partition, w as usize, i);
+            }
+        }
+    }
+
+    output
+}
+
+// Lexicographic depth first search: like LexBFS, but numbering a vertex prepends its number
+// to the labels of its unnumbered neighbours, so the most recent visit weighs the most
+// We keep the same ordered partition, except that the neighbours of a numbered vertex leave
+// their classes for new ones at the very front, since their labels are now the largest.
+// To keep the classes split off in the order of the old ones, we sort the neighbours by the
+// rank of their class, which makes this O(n + m log n)
+// Ties are broken in favour of the smallest vertex
+pub fn lex_dfs(graph: &Graph) -> Vec<i32> {
+    lex_dfs_with(graph, None, None)
+}
+
+// LexDFS starting from `start`, if given, with ties broken in favour of the vertex that comes
+// first in `ties`, or the smallest one if there is no tie-break ordering
+// Passing the scheme of a previous search as `ties` gives LexDFS+. This is O(n + m log n)
+pub fn lex_dfs_with(graph: &Graph, start: Option<u32>, ties: Option<&[i32]>) -> Vec<i32> {
+    let n = graph.node_count();
+    let initial: Vec<usize> = match ties {
+        Some(ties) => ties.iter().map(|&v| v as usize).collect(),
+        None => (0..n).collect(),
+    };
+
+    // neighbours sorted by their position in the initial order, which classes keep
+    let mut neighbours = vec![Vec::new(); n];
+    for &w in &initial {
+        for &v in graph.neighbors_slice(w as u32) {
+            neighbours[v as usize].push(w as u32);
+        }
+    }
+
+    let mut partition = Partition::new(initial.into_iter(), n);
+    let mut output = vec![0; n];
+    let mut numbered = vec![false; n];
+
+    if let Some(start) = start {
+        partition.split_to_front(start as usize, n);
+    }
+
+    for i in (0..n).rev() {
+        // "select": the head of the first class has the largest label
+        let v = partition.first_vertex();
+
+        // α(i) = v
+        output[i] = v as i32;
+        numbered[v] = true;
+        partition.remove(v);
+
+        // "update": move the unnumbered neighbours of v to the front, by class
+        let mut moved: Vec<u32> = neighbours[v]
             .iter()
-            .filter(|&neighbor| !numbered[*neighbor as usize])
-            .for_each(|w| {
-                sets[*w as usize].insert(Reverse(i));
-            });
+            .cloned()
+            .filter(|&w| !numbered[w as usize])
+            .collect();
+        moved.sort_by_key(|&w| partition.classes[partition.class_of[w as usize]].rank);
+
+        for &w in moved.iter().rev() {
+            partition.split_to_front(w as usize, i);
+        }
     }
 
     output
 }
 
-// Now, we're gonna catch the output from our naive lex-bfs
-// and test if it is a PES (EEP)
-pub fn is_pes(scheme: &[i32], graph: &Graph) -> bool {
-    let mut maybe_clique: HashSet<u32> = HashSet::with_capacity(scheme.len());
-    let mut eliminated_vertices: HashSet<u32> = HashSet::with_capacity(scheme.len());
-    let mut neighborhood = HashSet::with_capacity(scheme.len());
-    for eliminated_vertex in scheme {
-        let eliminated_vertex = *eliminated_vertex as u32;
-        eliminated_vertices.insert(eliminated_vertex);
-
-        neighborhood.extend(graph.neighbors_slice(eliminated_vertex).iter().cloned());
-
-        maybe_clique.clear();
-        maybe_clique.extend(neighborhood.difference(&eliminated_vertices));
-
-        neighborhood.clear();
-
-        // Dividing by two here because petgraph counts inbound and outbound vertices
-        let subgraph_edge_count = graph
-            .edge_references()
-            .filter(|x| {
-                maybe_clique.contains(&(x.source())) && maybe_clique.contains(&(x.target()))
-            })
-            .count()
-            / 2;
-
-        if subgraph_edge_count != complete_graph_edge_count(maybe_clique.len()) {
+// Checks that a scheme is the output of some LexDFS, by running the search along it
+// Each vertex must have a label no smaller than any other unnumbered one when it's
+// numbered. This is O(n(n + m))
+pub fn is_lex_dfs(scheme: &[i32], graph: &Graph) -> bool {
+    let n = graph.node_count();
+
+    // labels, in visit order, compared from the most recent visit
+    let mut labels: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut numbered = vec![false; n];
+
+    for (step, &v) in scheme.iter().rev().enumerate() {
+        let v = v as usize;
+
+        let largest = (0..n)
+            .filter(|&w| !numbered[w])
+            .all(|w| labels[w].iter().rev().cmp(labels[v].iter().rev()) != Ordering::Greater);
+        if !largest {
             return false;
         }
+
+        numbered[v] = true;
+        for &w in graph.neighbors_slice(v as u32) {
+            labels[w as usize].push(step);
+        }
     }
 
     true
 }
 
+// Tarjan and Yannakakis' Maximum Cardinality Search
+// Picks the unnumbered vertex with the most numbered neighbours, which we keep track of
+// with a bucket queue indexed by that count. The largest non-empty bucket only grows by one
+// at each step, so scanning down for it is amortized against the updates. This is O(n + m)
+pub fn mcs(graph: &Graph) -> Vec<i32> {
+    let n = graph.node_count();
+
+    let mut output = vec![0; n];
+    // number of numbered neighbours of each vertex
+    let mut weight = vec![0; n];
+    let mut numbered = vec![false; n];
+
+    // every vertex starts in bucket 0
+    let mut bucket = vec![NIL; n];
+    let mut prev: Vec<_> = (0..n).map(|v| v.wrapping_sub(1)).collect();
+    let mut next: Vec<_> = (1..=n).collect();
+    if n > 0 {
+        bucket[0] = 0;
+        next[n - 1] = NIL;
+    }
+
+    let mut max_weight = 0;
+
+    for i in (0..n).rev() {
+        // "select"
+        while bucket[max_weight] == NIL {
+            max_weight -= 1;
+        }
+        let v = bucket[max_weight];
+
+        // α(i) = v
+        output[i] = v as i32;
+        numbered[v] = true;
+        remove_from_bucket(&mut bucket, &mut prev, &mut next, weight[v], v);
+
+        // "update"
+        for &w in graph.neighbors_slice(v as u32) {
+            let w = w as usize;
+            if numbered[w] {
+                continue;
+            }
+
+            remove_from_bucket(&mut bucket, &mut prev, &mut next, weight[w], w);
+            weight[w] += 1;
+
+            prev[w] = NIL;
+            next[w] = bucket[weight[w]];
+            if next[w] != NIL {
+                prev[next[w]] = w;
+            }
+            bucket[weight[w]] = w;
+
+            max_weight = max_weight.max(weight[w]);
+        }
+    }
+
+    output
+}
+
+fn remove_from_bucket(
+    bucket: &mut [usize],
+    prev: &mut [usize],
+    next: &mut [usize],
+    b: usize,
+    v: usize,
+) {
+    if prev[v] == NIL {
+        bucket[b] = next[v];
+    } else {
+        next[prev[v]] = next[v];
+    }
+
+    if next[v] != NIL {
+        prev[next[v]] = prev[v];
+    }
+}
+
+// A naive implementation of Rose's LexBFS algorithm, as a generic search
+// Not optimal, as the search for an unnumbered vertex with the largest label is O(n) here
+// Thus, this is O(n²), as opposed to O(n) in Rose's paper
+// Ties are broken in favour of the smallest vertex, as in `lex_bfs`
+pub fn naive_lex_bfs(graph: &Graph) -> Vec<i32> {
+    search(graph, &LexBfs).order
+}
+
+// Now, we're gonna catch the output from our lex-bfs
+// and test if it is a PES (EEP)
+pub fn is_pes(scheme: &[i32], graph: &Graph) -> bool {
+    is_scheme(scheme, graph.node_count()) && scheme_violation(scheme, graph).is_none()
+}
+
+// Tarjan and Yannakakis' O(n + m) test: instead of checking that the later neighbours of
+// each vertex form a clique, we only check that they are adjacent to its parent,
+// the earliest eliminated of them. Those checks are deferred until the parent is eliminated,
+// so that each one costs O(1) against the marked neighbourhood of the parent
+// On failure, returns (v, parent, w), where parent and w are non-adjacent later neighbours of v
+// A scheme that isn't a permutation of the vertices has no such violation to show, and gets
+// None too, so `is_pes` rejects those first
+pub fn scheme_violation(scheme: &[i32], graph: &Graph) -> Option<(u32, u32, u32)> {
+    let n = graph.node_count();
+    if !is_scheme(scheme, n) {
+        return None;
+    }
+
+    let position = scheme_positions(scheme);
+
+    // (w, v) pairs where w must be adjacent to each vertex, as requested by its child v
+    let mut follow_up: Vec<Vec<(u32, u32)>> = vec![Vec::new(); n];
+    // marked[w] == v iff w is a neighbour of v
+    let mut marked = vec![NIL; n];
+
+    for &v in scheme {
+        let v = v as usize;
+        let neighbors = graph.neighbors_slice(v as u32);
+
+        for &w in neighbors {
+            marked[w as usize] = v;
+        }
+
+        if let Some(&(w, child)) = follow_up[v].iter().find(|&&(w, _)| marked[w as usize] != v) {
+            return Some((child, v as u32, w));
+        }
+
+        let later = || {
+            neighbors
+                .iter()
+                .cloned()
+                .filter(|&w| position[w as usize] > position[v])
+        };
+
+        if let Some(parent) = later().min_by_key(|&w| position[w as usize]) {
+            follow_up[parent as usize]
+                .extend(later().filter(|&w| w != parent).map(|w| (w, v as u32)));
+        }
+    }
+
+    None
+}
+
+// Extracts a chordless cycle of length at least 4 from a scheme that is not a PES
+// The scheme must come from LexBFS or MCS: for those, the two non-adjacent later neighbours
+// of the violating vertex v are always joined by a path avoiding the rest of N(v)
+// A shortest such path closes an induced cycle through v. This is O(n + m)
+pub fn hole_from_scheme(scheme: &[i32], graph: &Graph) -> Option<Vec<u32>> {
+    let (v, x, y) = scheme_violation(scheme, graph)?;
+    let n = graph.node_count();
+
+    // v and its neighbours, except for the ends of the path, can't be on it
+    let mut blocked = vec![false; n];
+    blocked[v as usize] = true;
+    for &w in graph.neighbors_slice(v) {
+        blocked[w as usize] = w != x && w != y;
+    }
+
+    // BFS from x, stopping as soon as y is reached
+    let mut came_from = vec![NIL; n];
+    let mut queue = VecDeque::new();
+    came_from[x as usize] = x as usize;
+    queue.push_back(x);
+
+    while let Some(u) = queue.pop_front() {
+        if u == y {
+            break;
+        }
+
+        for &w in graph.neighbors_slice(u) {
+            if !blocked[w as usize] && came_from[w as usize] == NIL {
+                came_from[w as usize] = u as usize;
+                queue.push_back(w);
+            }
+        }
+    }
+
+    if came_from[y as usize] == NIL {
+        return None;
+    }
+
+    // walking back from y gives the cycle as v, y, ..., x
+    let mut hole = vec![v, y];
+    let mut u = y as usize;
+    while u != x as usize {
+        u = came_from[u];
+        hole.push(u as u32);
+    }
+
+    Some(hole)
+}
+
+// Either a PES, which proves the graph is chordal, or a hole proving it is not
+pub fn is_chordal_with_certificate(graph: &Graph) -> Result<Vec<i32>, NotChordal> {
+    let scheme = lex_bfs(graph);
+
+    match hole_from_scheme(&scheme, graph) {
+        Some(hole) => Err(NotChordal { hole }),
+        None => Ok(scheme),
+    }
+}
+
+pub fn find_hole(graph: &Graph) -> Option<Vec<u32>> {
+    is_chordal_with_certificate(graph).err().map(|e| e.hole)
+}
+
+// The search used to compute the elimination scheme tested by `is_chordal_with`
+// Both produce a PES iff the graph is chordal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Search {
+    #[default]
+    LexBfs,
+    Mcs,
+}
+
+impl Search {
+    pub fn scheme(self, graph: &Graph) -> Vec<i32> {
+        match self {
+            Search::LexBfs => lex_bfs(graph),
+            Search::Mcs => mcs(graph),
+        }
+    }
+}
+
 pub fn is_chordal(graph: &Graph) -> bool {
-    let scheme = naive_lex_bfs(&graph);
+    is_chordal_with(graph, Search::default())
+}
+
+pub fn is_chordal_with(graph: &Graph, search: Search) -> bool {
+    let scheme = search.scheme(graph);
 
     is_pes(&scheme, graph)
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use crate::common::{graph_from_edges, graph_from_reader};
+    use std::fs::File;
+    use std::io::BufReader;
     #[test]
     fn diamond_graph_serial() {
         let mut graph = Csr::new();
@@ -229,4 +896,324 @@ mod tests {
 
         assert_eq!(is_pes(&res, &graph), false);
     }
+
+    #[test]
+    fn gem_graph_lex_bfs() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(b, d, ());
+        graph.add_edge(b, e, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, e, ());
+        graph.add_edge(e, a, ());
+
+        let res = lex_bfs(&graph);
+
+        println!("{:?}", res);
+
+        let mut sorted = res.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2, 3, 4]);
+        assert!(is_pes(&res, &graph));
+    }
+
+    #[test]
+    fn not_chordal_lex_bfs() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        // a C4 with a pendant vertex
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, a, ());
+        graph.add_edge(d, e, ());
+
+        let res = lex_bfs(&graph);
+
+        assert!(!is_pes(&res, &graph));
+        assert!(!is_chordal(&graph));
+    }
+
+    #[test]
+    fn from_file_serial() {
+        let file = File::open("k100.txt").unwrap();
+
+        let graph = graph_from_reader(BufReader::new(file)).unwrap();
+
+        assert!(is_chordal(&graph));
+    }
+
+    #[test]
+    fn chordal_with_bad_scheme() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+
+        // eliminating the middle vertex of a path first fills in a-c
+        assert!(!is_pes(&[1, 0, 2], &graph));
+        assert!(is_pes(&[0, 1, 2], &graph));
+
+        // and a scheme missing or repeating vertices isn't one at all
+        assert!(!is_pes(&[0, 1], &graph));
+        assert!(!is_pes(&[0, 1, 1], &graph));
+        assert!(!is_pes(&[0, 1, 3], &graph));
+        assert_eq!(scheme_violation(&[0, 1], &graph), None);
+    }
+
+    #[test]
+    fn long_chordal_mcs() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+        let f = graph.add_node(());
+        let g = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+
+        graph.add_edge(g, f, ());
+        graph.add_edge(f, e, ());
+
+        graph.add_edge(g, b, ());
+        graph.add_edge(b, f, ());
+        graph.add_edge(f, c, ());
+        graph.add_edge(c, e, ());
+
+        graph.add_edge(a, g, ());
+        graph.add_edge(e, d, ());
+
+        let res = mcs(&graph);
+
+        println!("{:?}", res);
+
+        assert!(is_pes(&res, &graph));
+        assert!(is_chordal_with(&graph, Search::Mcs));
+    }
+
+    #[test]
+    fn not_chordal_mcs() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, a, ());
+
+        assert!(!is_chordal_with(&graph, Search::Mcs));
+    }
+
+    #[test]
+    fn hole_in_c5_with_pendant() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+        let f = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, e, ());
+        graph.add_edge(e, a, ());
+        graph.add_edge(c, f, ());
+
+        let mut hole = find_hole(&graph).expect("C5 has a hole");
+
+        println!("{:?}", hole);
+
+        hole.sort();
+        assert_eq!(hole, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn hole_with_chords_elsewhere() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        // a C4 a-b-c-d plus a vertex e adjacent to all of them
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, a, ());
+        graph.add_edge(e, a, ());
+        graph.add_edge(e, b, ());
+        graph.add_edge(e, c, ());
+        graph.add_edge(e, d, ());
+
+        let hole = find_hole(&graph).expect("wheel has a hole");
+
+        assert_eq!(hole.len(), 4);
+        assert!(!hole.contains(&4));
+        for (i, &v) in hole.iter().enumerate() {
+            let w = hole[(i + 1) % hole.len()];
+            let across = hole[(i + 2) % hole.len()];
+
+            assert!(graph.contains_edge(v, w));
+            assert!(!graph.contains_edge(v, across));
+        }
+    }
+
+    #[test]
+    fn chordal_certificate() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(a, c, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(b, d, ());
+        graph.add_edge(c, d, ());
+
+        let scheme = is_chordal_with_certificate(&graph).unwrap();
+
+        assert!(is_pes(&scheme, &graph));
+        assert_eq!(find_hole(&graph), None);
+    }
+
+    #[test]
+    fn lex_bfs_plus_ties() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        // a path a-b-c-d
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+
+        // visits a, b, c, d
+        let res = lex_bfs(&graph);
+        assert_eq!(res, vec![3, 2, 1, 0]);
+
+        // d was visited last, so it starts the next sweep
+        let plus = lex_bfs_plus(&graph, &res);
+        assert_eq!(plus, vec![0, 1, 2, 3]);
+    }
+    #[test]
+    fn lex_dfs_goes_deep() {
+        // a C6 with a pendant on 0: LexBFS would visit both neighbours of 0 first
+        let graph = graph_from_edges(
+            7,
+            vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0), (0, 6)],
+        );
+
+        let res = lex_dfs(&graph);
+        println!("{:?}", res);
+
+        // visits 0, 1, 2, 3, 4, 5, then 6
+        assert_eq!(res, vec![6, 5, 4, 3, 2, 1, 0]);
+        assert!(is_lex_dfs(&res, &graph));
+        assert!(!is_lex_dfs(&lex_bfs(&graph), &graph));
+    }
+
+    #[test]
+    fn lex_dfs_start_and_ties() {
+        let graph = graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3)]);
+
+        // from the middle, the smallest neighbour goes first, and the search goes on past it
+        let res = lex_dfs_with(&graph, Some(2), None);
+        assert_eq!(res, vec![3, 0, 1, 2]);
+
+        // or the one coming first in the tie-break ordering
+        let res = lex_dfs_with(&graph, Some(2), Some(&[3, 2, 1, 0]));
+        assert_eq!(res, vec![0, 1, 3, 2]);
+        assert!(is_lex_dfs(&res, &graph));
+
+        // with no start, the tie-break ordering picks it too, as in LexDFS+
+        let plus = lex_dfs_with(&graph, None, Some(&res));
+        assert_eq!(plus[3], 0);
+    }
+
+    #[test]
+    fn lex_bfs_star_ties() {
+        // a triangle 0, 1, 3, with a pendant 2 on 0
+        let graph = graph_from_edges(4, vec![(0, 1), (0, 2), (0, 3), (1, 3)]);
+        let (a, b) = ([3, 2, 1, 0], [2, 3, 1, 0]);
+
+        // a would start from 3, but b from 2, whose neighbourhood is smaller
+        assert_eq!(lex_bfs_plus(&graph, &a), vec![2, 0, 1, 3]);
+        assert_eq!(lex_bfs_star(&graph, &a, &b), vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn lex_bfs_star_adjacent_ties() {
+        // the same graph, where a starts from 0 and b from its neighbour 1
+        let graph = graph_from_edges(4, vec![(0, 1), (0, 2), (0, 3), (1, 3)]);
+        let (a, b) = ([0, 1, 2, 3], [1, 3, 0, 2]);
+
+        // besides each other, 1 only sees 3, and 0 sees both 2 and 3
+        assert_eq!(lex_bfs_plus(&graph, &a), vec![2, 3, 1, 0]);
+        assert_eq!(lex_bfs_star(&graph, &a, &b), vec![2, 3, 0, 1]);
+
+        // with nothing for b to break differently, it is LexBFS+
+        assert_eq!(lex_bfs_star(&graph, &a, &a), lex_bfs_plus(&graph, &a));
+    }
+
+    #[test]
+    fn multi_sweep() {
+        let graph = graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3)]);
+        let sweeps = MultiSweep::new(vec![
+            Sweep::LexBfs,
+            Sweep::LexBfsPlus,
+            Sweep::LexBfsPlus,
+            Sweep::LexBfsStar,
+        ]);
+
+        let orderings = sweeps.run(&graph);
+        println!("{:?}", orderings);
+
+        assert_eq!(orderings.len(), 4);
+        assert_eq!(orderings[0], naive_lex_bfs(&graph));
+        assert_eq!(orderings[1], vec![0, 1, 2, 3]);
+        assert_eq!(orderings[2], orderings[0]);
+        assert_eq!(
+            orderings[3],
+            lex_bfs_star(&graph, &orderings[1], &orderings[2])
+        );
+        assert_eq!(sweeps.last(&graph), orderings.last().cloned());
+    }
 }
@@ -0,0 +1,197 @@
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::common::ForbiddenSubgraph;
+use crate::serial::find_hole;
+
+type Graph = Csr<(), (), Undirected>;
+
+// A split graph's vertices, partitioned into a clique and an independent set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitPartition {
+    pub clique: Vec<u32>,
+    pub independent_set: Vec<u32>,
+}
+
+// Hammer and Simeone's degree sequence test
+// With degrees d1 ≥ ... ≥ dn and m the largest i with di ≥ i - 1, the graph is split iff
+// d1 + ... + dm = m(m - 1) + dm+1 + ... + dn, in which case the m vertices of largest degree
+// form a clique and the others an independent set. This is O(n + m)
+pub fn split_partition(graph: &Graph) -> Option<SplitPartition> {
+    let n = graph.node_count();
+    let degree = |v: u32| graph.neighbors_slice(v).len();
+
+    // counting sort, by decreasing degree
+    let mut by_degree = vec![Vec::new(); n];
+    for v in 0..n as u32 {
+        by_degree[degree(v)].push(v);
+    }
+    let sorted: Vec<u32> = by_degree.into_iter().rev().flatten().collect();
+
+    let m = sorted
+        .iter()
+        .enumerate()
+        .take_while(|&(i, &v)| degree(v) >= i)
+        .count();
+
+    let (clique, independent_set) = sorted.split_at(m);
+    let clique_degrees: usize = clique.iter().map(|&v| degree(v)).sum();
+    let independent_degrees: usize = independent_set.iter().map(|&v| degree(v)).sum();
+
+    if clique_degrees != m * m.saturating_sub(1) + independent_degrees {
+        return None;
+    }
+
+    let mut clique = clique.to_vec();
+    let mut independent_set = independent_set.to_vec();
+    clique.sort();
+    independent_set.sort();
+
+    Some(SplitPartition {
+        clique,
+        independent_set,
+    })
+}
+
+pub fn is_split(graph: &Graph) -> bool {
+    split_partition(graph).is_some()
+}
+
+// Földes and Hammer: a graph is split iff it has no induced 2K2, C4 or C5
+// On failure, a hole of G gives one of those directly. Otherwise G is chordal, so the hole
+// must be in its complement, and can only be a 2K2 of G. We look for it by trying each edge
+// against the edges left after removing both closed neighbourhoods, which is O(m(n + m))
+// in the worst case, but only runs after recognition already failed in O(n + m)
+pub fn split_certificate(graph: &Graph) -> Result<SplitPartition, ForbiddenSubgraph> {
+    if let Some(partition) = split_partition(graph) {
+        return Ok(partition);
+    }
+
+    if let Some(hole) = find_hole(graph) {
+        return Err(match hole.len() {
+            4 => ForbiddenSubgraph::C4([hole[0], hole[1], hole[2], hole[3]]),
+            5 => ForbiddenSubgraph::C5([hole[0], hole[1], hole[2], hole[3], hole[4]]),
+            // two edges at distance two along a longer hole
+            _ => ForbiddenSubgraph::TwoK2([hole[0], hole[1], hole[3], hole[4]]),
+        });
+    }
+
+    Err(ForbiddenSubgraph::TwoK2(
+        find_two_k2(graph).expect("chordal graph that is not split has a 2K2"),
+    ))
+}
+
+fn find_two_k2(graph: &Graph) -> Option<[u32; 4]> {
+    let n = graph.node_count();
+    // seen[v] == (a, b) iff v is in the closed neighbourhood of edge a-b
+    let mut seen = vec![(u32::MAX, u32::MAX); n];
+
+    for a in 0..n as u32 {
+        for &b in graph.neighbors_slice(a).iter().filter(|&&b| a < b) {
+            seen[a as usize] = (a, b);
+            for &v in graph
+                .neighbors_slice(a)
+                .iter()
+                .chain(graph.neighbors_slice(b))
+            {
+                seen[v as usize] = (a, b);
+            }
+
+            for c in (0..n as u32).filter(|&c| seen[c as usize] != (a, b)) {
+                let d = graph
+                    .neighbors_slice(c)
+                    .iter()
+                    .find(|&&d| seen[d as usize] != (a, b));
+
+                if let Some(&d) = d {
+                    return Some([a, b, c, d]);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::graph_from_edges;
+    use crate::test_util::assert_induces;
+
+    // a clique 0..k, and k..n independent vertices, each adjacent to a few clique vertices
+    fn split_graph(k: u32, n: u32) -> Graph {
+        let clique = (0..k).flat_map(|a| (a + 1..k).map(move |b| (a, b)));
+        let independent = (k..n).flat_map(|x| {
+            (0..k)
+                .filter(move |&c| (x * 7 + c * 3) % 5 < 2)
+                .map(move |c| (x, c))
+        });
+
+        graph_from_edges(n as usize, clique.chain(independent))
+    }
+
+    #[test]
+    fn generated_split_graphs() {
+        for k in 0..6 {
+            for n in k..12 {
+                let graph = split_graph(k, n);
+                let partition = split_certificate(&graph).unwrap();
+
+                for &a in &partition.clique {
+                    for &b in partition.clique.iter().filter(|&&b| b != a) {
+                        assert!(graph.contains_edge(a, b));
+                    }
+                }
+
+                for &a in &partition.independent_set {
+                    for &b in &partition.independent_set {
+                        assert!(!graph.contains_edge(a, b));
+                    }
+                }
+
+                assert_eq!(
+                    partition.clique.len() + partition.independent_set.len(),
+                    n as usize
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cycles_are_not_split() {
+        let c4 = graph_from_edges(4, (0..4).map(|v| (v, (v + 1) % 4)));
+        match split_certificate(&c4) {
+            Err(ForbiddenSubgraph::C4(cycle)) => {
+                assert_induces(&c4, &cycle, &[(0, 1), (1, 2), (2, 3), (3, 0)])
+            }
+            other => panic!("expected a C4, got {:?}", other),
+        }
+
+        let c5 = graph_from_edges(5, (0..5).map(|v| (v, (v + 1) % 5)));
+        assert!(matches!(
+            split_certificate(&c5),
+            Err(ForbiddenSubgraph::C5(_))
+        ));
+
+        let c7 = graph_from_edges(7, (0..7).map(|v| (v, (v + 1) % 7)));
+        match split_certificate(&c7) {
+            Err(ForbiddenSubgraph::TwoK2(edges)) => assert_induces(&c7, &edges, &[(0, 1), (2, 3)]),
+            other => panic!("expected a 2K2, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn chordal_but_not_split() {
+        // a path on 5 vertices: chordal, but its ends form a 2K2
+        let graph = graph_from_edges(5, (0..4).map(|v| (v, v + 1)));
+
+        assert!(!is_split(&graph));
+        match split_certificate(&graph) {
+            Err(ForbiddenSubgraph::TwoK2(edges)) => {
+                assert_induces(&graph, &edges, &[(0, 1), (2, 3)])
+            }
+            other => panic!("expected a 2K2, got {:?}", other),
+        }
+    }
+}
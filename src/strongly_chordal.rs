@@ -0,0 +1,376 @@
+use std::cmp::Ordering;
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::common::{induced_subgraph, is_scheme, scheme_positions, ForbiddenSubgraph};
+use crate::serial::find_hole;
+
+type Graph = Csr<(), (), Undirected>;
+
+// Symmetric doubly lexical ordering of the closed neighbourhood matrix of G: a single order
+// of the vertices, used for both rows and columns, under which both read as non-increasing
+// binary strings, the first row and column being the most significant. Lubiw shows that one
+// always exists
+// We place the vertices one at a time, keeping the ones left in an ordered partition, where
+// each class holds the vertices whose rows agree on the columns placed so far. The next vertex
+// comes from the first class, and is the one with the most neighbours in it, ties broken by its
+// neighbours in the following classes, and so on. Then every class is split by its closed
+// neighbourhood, with the neighbours going first. Any other vertex of the first class either
+// misses one of those neighbours, which come first, or agrees with it up to a class where
+// it has fewer, so its row is no larger
+// Each step scans the vertices left and sorts the neighbour classes of every vertex in the
+// first class, so this is O(n² + nm log n), against the O(m log n) of Paige and Tarjan's
+// partition refinement for doubly lexical orderings, which this is not
+pub fn doubly_lexical_ordering(graph: &Graph) -> Vec<u32> {
+    let n = graph.node_count();
+    let mut order: Vec<u32> = (0..n as u32).collect();
+    let mut position: Vec<usize> = (0..n).collect();
+    // class[i] is the position where the class of order[i] begins
+    let mut class = vec![0; n];
+    // marked[w] == i iff w is a neighbour of the i-th vertex placed
+    let mut marked = vec![usize::MAX; n];
+
+    for i in 0..n {
+        let first_class = i..(i..n).find(|&j| class[j] != class[i]).unwrap_or(n);
+
+        // the number of closed neighbours in each class from here on, by class
+        let counts = |v: u32| {
+            let mut classes: Vec<usize> = graph
+                .neighbors_slice(v)
+                .iter()
+                .chain(Some(&v))
+                .map(|&w| position[w as usize])
+                .filter(|&p| p >= i)
+                .map(|p| class[p])
+                .collect();
+            classes.sort();
+
+            let mut counts: Vec<(usize, usize)> = Vec::new();
+            for c in classes {
+                match counts.last_mut() {
+                    Some((last, count)) if *last == c => *count += 1,
+                    _ => counts.push((c, 1)),
+                }
+            }
+            counts
+        };
+
+        let best = first_class
+            .map(|p| (counts(order[p]), p))
+            .max_by(|(a, _), (b, _)| counts_cmp(a, b).then(Ordering::Greater))
+            .map(|(_, p)| p)
+            .unwrap();
+
+        let pivot = order[best];
+        order.swap(i, best);
+        position[order[best] as usize] = best;
+        position[pivot as usize] = i;
+        for &w in graph.neighbors_slice(pivot) {
+            marked[w as usize] = i;
+        }
+
+        // split every class after the pivot, keeping the pivot's neighbours first
+        let mut next = i + 1;
+        while next < n {
+            let end = (next..n).find(|&j| class[j] != class[next]).unwrap_or(n);
+            let (adjacent, rest): (Vec<u32>, Vec<u32>) = order[next..end]
+                .iter()
+                .partition(|&&w| marked[w as usize] == i);
+
+            for (j, &w) in adjacent.iter().chain(&rest).enumerate() {
+                order[next + j] = w;
+                position[w as usize] = next + j;
+                class[next + j] = if j < adjacent.len() {
+                    next
+                } else {
+                    next + adjacent.len()
+                };
+            }
+
+            next = end;
+        }
+        class[i] = i;
+    }
+
+    order
+}
+
+// Compares the neighbour counts, per class, of two vertices, with classes sorted by position
+// A vertex with neighbours in an earlier class, or more of them in the first class where they
+// differ, is larger
+fn counts_cmp(a: &[(usize, usize)], b: &[(usize, usize)]) -> Ordering {
+    for (&(x, p), &(y, q)) in a.iter().zip(b) {
+        if x != y {
+            return y.cmp(&x);
+        }
+        if p != q {
+            return p.cmp(&q);
+        }
+    }
+
+    a.len().cmp(&b.len())
+}
+
+// Farber: a scheme is a strong elimination ordering iff, for each vertex v, the closed
+// neighbourhoods of its later neighbours (and itself), restricted to the vertices eliminated
+// no earlier than v, are nested in elimination order. Since v comes first, this makes it
+// simplicial, so every strong elimination ordering is a PES
+// We only compare consecutive neighbours, which is O(Σ d(v)² log n)
+pub fn is_strong_elimination_ordering(scheme: &[i32], graph: &Graph) -> bool {
+    if !is_scheme(scheme, graph.node_count()) {
+        return false;
+    }
+
+    let position = scheme_positions(scheme);
+
+    scheme.iter().all(|&v| {
+        let v = v as u32;
+        let first = position[v as usize];
+
+        let mut later: Vec<u32> = graph
+            .neighbors_slice(v)
+            .iter()
+            .cloned()
+            .filter(|&w| position[w as usize] > first)
+            .collect();
+        later.push(v);
+        later.sort_by_key(|&w| position[w as usize]);
+
+        later.windows(2).all(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+
+            graph
+                .neighbors_slice(a)
+                .iter()
+                .cloned()
+                .chain(Some(a))
+                .filter(|&w| position[w as usize] >= first)
+                .all(|w| w == b || graph.contains_edge(b, w))
+        })
+    })
+}
+
+// Lubiw: G is strongly chordal iff its closed neighbourhood matrix is totally balanced,
+// in which case any symmetric doubly lexical ordering of it, read backwards, is a strong
+// elimination ordering. We build that candidate and check it
+pub fn strong_elimination_ordering(graph: &Graph) -> Option<Vec<i32>> {
+    let order = doubly_lexical_ordering(graph);
+    let scheme: Vec<i32> = order.into_iter().rev().map(|v| v as i32).collect();
+
+    if is_strong_elimination_ordering(&scheme, graph) {
+        Some(scheme)
+    } else {
+        None
+    }
+}
+
+pub fn is_strongly_chordal(graph: &Graph) -> bool {
+    strong_elimination_ordering(graph).is_some()
+}
+
+// Farber: a chordal graph is strongly chordal iff it has no induced k-sun, k >= 3
+// On failure, a graph that isn't chordal gives a hole. Otherwise, we delete every vertex
+// whose removal leaves a graph that still isn't strongly chordal. What remains is a minimal
+// chordal graph that isn't strongly chordal, which must be a sun. That takes n more
+// recognitions, but only runs after recognition already failed
+pub fn strongly_chordal_certificate(graph: &Graph) -> Result<Vec<i32>, ForbiddenSubgraph> {
+    if let Some(scheme) = strong_elimination_ordering(graph) {
+        return Ok(scheme);
+    }
+
+    if let Some(hole) = find_hole(graph) {
+        return Err(ForbiddenSubgraph::Hole(hole));
+    }
+
+    let mut kept: Vec<u32> = (0..graph.node_count() as u32).collect();
+    let mut i = 0;
+    while i < kept.len() {
+        let v = kept.remove(i);
+
+        if is_strongly_chordal(&induced_subgraph(graph, &kept)) {
+            kept.insert(i, v);
+            i += 1;
+        }
+    }
+
+    Err(sun(graph, &kept))
+}
+
+// Lays out the vertices of a k-sun, starting from any of its degree 2 vertices
+// and walking around the clique through them
+fn sun(graph: &Graph, vertices: &[u32]) -> ForbiddenSubgraph {
+    let mut inside = vec![false; graph.node_count()];
+    for &v in vertices {
+        inside[v as usize] = true;
+    }
+    let neighbours = |v: u32| {
+        graph
+            .neighbors_slice(v)
+            .iter()
+            .cloned()
+            .filter(|&w| inside[w as usize])
+            .collect::<Vec<u32>>()
+    };
+
+    let ears: Vec<u32> = vertices
+        .iter()
+        .cloned()
+        .filter(|&v| neighbours(v).len() == 2)
+        .collect();
+
+    let first = neighbours(ears[0]);
+    let mut clique = first.clone();
+    let mut independent_set = vec![ears[0]];
+
+    while independent_set.len() < ears.len() {
+        let (&previous, &w) = (independent_set.last().unwrap(), clique.last().unwrap());
+        let ear = ears
+            .iter()
+            .cloned()
+            .find(|&u| u != previous && graph.contains_edge(u, w))
+            .unwrap();
+        let next = neighbours(ear).into_iter().find(|&x| x != w).unwrap();
+
+        independent_set.push(ear);
+        if next != first[0] {
+            clique.push(next);
+        }
+    }
+
+    ForbiddenSubgraph::Sun {
+        clique,
+        independent_set,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::graph_from_edges;
+    use crate::serial::{is_pes, lex_bfs};
+
+    fn k_sun(k: u32) -> Graph {
+        let clique = (0..k).flat_map(|a| (a + 1..k).map(move |b| (a, b)));
+        let ears = (0..k).flat_map(|i| vec![(k + i, i), (k + i, (i + 1) % k)]);
+
+        graph_from_edges(2 * k as usize, clique.chain(ears))
+    }
+
+    #[test]
+    fn doubly_lexical() {
+        let graph = graph_from_edges(
+            6,
+            vec![(0, 1), (1, 2), (2, 3), (3, 4), (1, 5), (2, 5), (4, 5)],
+        );
+        let order = doubly_lexical_ordering(&graph);
+
+        println!("{:?}", order);
+
+        let read = |v: u32| {
+            order
+                .iter()
+                .map(|&w| v == w || graph.contains_edge(v, w))
+                .collect::<Vec<bool>>()
+        };
+        assert!(order.windows(2).all(|p| read(p[0]) >= read(p[1])));
+        assert!([1, 2, 5].contains(&order[0]));
+    }
+
+    #[test]
+    fn trees_are_strongly_chordal() {
+        let graph = graph_from_edges(7, vec![(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (2, 6)]);
+        let scheme = strong_elimination_ordering(&graph).unwrap();
+
+        assert!(is_strong_elimination_ordering(&scheme, &graph));
+        assert!(is_pes(&scheme, &graph));
+    }
+
+    #[test]
+    fn bad_strong_schemes() {
+        // a path 0 - 1 - 2
+        let graph = graph_from_edges(3, vec![(0, 1), (1, 2)]);
+
+        assert!(is_strong_elimination_ordering(&[0, 1, 2], &graph));
+        assert!(!is_strong_elimination_ordering(&[0, 0, 1], &graph));
+        assert!(!is_strong_elimination_ordering(&[0, 1, 7], &graph));
+        assert!(!is_strong_elimination_ordering(&[0, 1], &graph));
+    }
+
+    #[test]
+    fn complete_graph_is_strongly_chordal() {
+        let graph = graph_from_edges(6, (0..6).flat_map(|a| (a + 1..6).map(move |b| (a, b))));
+
+        assert!(is_strongly_chordal(&graph));
+    }
+
+    #[test]
+    fn suns_are_not_strongly_chordal() {
+        for k in 3..7 {
+            let graph = k_sun(k);
+
+            // chordal, but no perfect elimination scheme is strong
+            let scheme = lex_bfs(&graph);
+            assert!(is_pes(&scheme, &graph));
+            assert!(!is_strong_elimination_ordering(&scheme, &graph));
+
+            match strongly_chordal_certificate(&graph) {
+                Err(ForbiddenSubgraph::Sun {
+                    clique,
+                    independent_set,
+                }) => {
+                    println!("{:?} {:?}", clique, independent_set);
+                    assert_eq!(clique.len(), k as usize);
+                    assert!(clique.iter().all(|&w| w < k));
+                    for (i, &u) in independent_set.iter().enumerate() {
+                        assert!(u >= k);
+                        assert!(graph.contains_edge(u, clique[i]));
+                        assert!(graph.contains_edge(u, clique[(i + 1) % k as usize]));
+                    }
+                }
+                other => panic!("expected a sun, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn sun_inside_a_larger_graph() {
+        // the 3-sun on 0..6, plus a pendant path and a vertex seeing the whole clique
+        let mut edges = vec![
+            (0, 1),
+            (1, 2),
+            (0, 2),
+            (3, 0),
+            (3, 1),
+            (4, 1),
+            (4, 2),
+            (5, 2),
+            (5, 0),
+        ];
+        edges.extend(vec![(3, 6), (6, 7), (8, 0), (8, 1), (8, 2)]);
+        let graph = graph_from_edges(9, edges);
+
+        match strongly_chordal_certificate(&graph) {
+            Err(ForbiddenSubgraph::Sun {
+                mut clique,
+                mut independent_set,
+            }) => {
+                clique.sort();
+                independent_set.sort();
+                assert_eq!(clique, vec![0, 1, 2]);
+                assert_eq!(independent_set, vec![3, 4, 5]);
+            }
+            other => panic!("expected a sun, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn holes_are_not_strongly_chordal() {
+        let graph = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+
+        match strongly_chordal_certificate(&graph) {
+            Err(ForbiddenSubgraph::Hole(hole)) => assert_eq!(hole.len(), 5),
+            other => panic!("expected a hole, got {:?}", other),
+        }
+    }
+}
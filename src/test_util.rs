@@ -0,0 +1,29 @@
+// Helpers shared by the unit tests
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+type Graph = Csr<(), (), Undirected>;
+
+pub(crate) fn same_graph(a: &Graph, b: &Graph) -> bool {
+    a.node_count() == b.node_count()
+        && (0..a.node_count() as u32).all(|v| a.neighbors_slice(v) == b.neighbors_slice(v))
+}
+
+// Checks that the vertices induce exactly the given edges, between their indices
+pub(crate) fn assert_induces(graph: &Graph, vertices: &[u32], edges: &[(usize, usize)]) {
+    for i in 0..vertices.len() {
+        for j in i + 1..vertices.len() {
+            let edge = edges.contains(&(i, j)) || edges.contains(&(j, i));
+            assert_eq!(graph.contains_edge(vertices[i], vertices[j]), edge);
+        }
+    }
+}
+
+// xorshift, so that the random tests are reproducible
+pub(crate) fn random(state: &mut u64, below: usize) -> usize {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    (*state % below as u64) as usize
+}
@@ -1,203 +1,156 @@
-use std::cmp::{Eq, Reverse};
-use std::collections::{BTreeSet, HashSet};
-use std::hash::Hash;
-use std::marker::Send;
+use std::cmp::Ordering;
 use std::ptr::NonNull;
-use std::slice;
 
 use petgraph::csr::Csr;
-use petgraph::visit::{EdgeRef, IntoEdgeReferences, NodeIndexable};
 use petgraph::Undirected;
 
 use scoped_threadpool::Pool;
 
 use crossbeam::channel::unbounded;
 
-use crate::common::{complete_graph_edge_count, rose_cmp};
+use crate::common::{children_covered, is_scheme, scheme_parent, scheme_positions};
+use crate::search::{search_with, LexBfs, LexDfs, Scan};
 
 type Graph = Csr<(), (), Undirected>;
 
-struct Sendable<T>(*const T);
 struct MutSendable<T>(NonNull<T>);
 
-unsafe impl<T> Send for Sendable<T> {}
 unsafe impl<T> Send for MutSendable<T> {}
 
-fn select2(pool: &mut Pool, sets: &[BTreeSet<Reverse<usize>>], numbered: &Vec<bool>) -> usize {
-    let enumerated: Vec<(usize, &BTreeSet<Reverse<usize>>)> = sets.iter().enumerate().collect();
-    let mut element_count = sets.len();
-
-    // filter
-    let (sender, receiver) = unbounded();
-    let chunk_size = (element_count / pool.thread_count() as usize) + 1;
-    pool.scoped(|scope| {
-        for chunk in enumerated.chunks(chunk_size) {
-            let sender = sender.clone();
-            scope.execute(move || {
-                chunk
-                    .iter()
-                    .filter(|(idx, _)| !numbered[*idx])
-                    .for_each(|&pair| sender.send(pair).unwrap());
-            });
-        }
-    });
-
-    drop(sender);
-
-    let mut res: Vec<(usize, &BTreeSet<Reverse<usize>>)> = receiver.iter().collect();
-
-    // reduce
-    while element_count != 1 {
+// Scans the vertices in chunks, one per thread of the pool
+// Each chunk sends back its best candidate along with its index, so that the chunks are
+// reduced in order, whatever order they come back in
+pub struct Threads<'a>(pub &'a mut Pool);
+
+impl Scan for Threads<'_> {
+    fn best<C, O>(&mut self, n: usize, candidate: C, order: O) -> Option<usize>
+    where
+        C: Fn(usize) -> bool + Sync,
+        O: Fn(usize, usize) -> Ordering + Sync,
+    {
+        let chunk_size = (n / self.0.thread_count() as usize) + 1;
+        let (candidate, order) = (&candidate, &order);
+
+        // filter and reduce each chunk
         let (sender, receiver) = unbounded();
-        let chunk_size = (element_count / pool.thread_count() as usize).max(2);
-        pool.scoped(|scope| {
-            for chunk in res.chunks(chunk_size) {
+        self.0.scoped(|scope| {
+            for (index, start) in (0..n).step_by(chunk_size).enumerate() {
                 let sender = sender.clone();
                 scope.execute(move || {
-                    let local_max = chunk
-                        .iter()
-                        .max_by(|(_, a), (_, b)| rose_cmp(a, b))
-                        .unwrap();
+                    let local_best = (start..n.min(start + chunk_size))
+                        .filter(|&v| candidate(v))
+                        .max_by(|&a, &b| order(a, b));
 
-                    sender.send(*local_max).unwrap();
+                    sender.send((index, local_best)).unwrap();
                 });
             }
         });
 
         drop(sender);
-        res = receiver.iter().collect();
 
-        element_count = res.len();
+        // reduce the chunks
+        let mut bests: Vec<_> = receiver.iter().collect();
+        bests.sort_unstable_by_key(|&(index, _)| index);
+
+        bests
+            .into_iter()
+            .filter_map(|(_, best)| best)
+            .max_by(|&a, &b| order(a, b))
     }
 
-    res[0].0
+    fn update<L, U>(&mut self, labels: &mut [L], vertices: &[u32], update: U)
+    where
+        L: Send,
+        U: Fn(&mut L) + Sync,
+    {
+        let chunk_size = (vertices.len() / self.0.thread_count() as usize) + 1;
+        let update = &update;
+
+        self.0.scoped(|scope| {
+            for chunk in vertices.chunks(chunk_size) {
+                let labels_ptr = MutSendable(NonNull::new(labels.as_mut_ptr()).unwrap());
+
+                // the vertices are distinct, so no two threads touch the same label
+                scope.execute(move || unsafe {
+                    let ptr = labels_ptr.0.as_ptr();
+                    for &w in chunk {
+                        update(&mut *ptr.add(w as usize));
+                    }
+                });
+            }
+        });
+    }
 }
 
 pub fn naive_lex_bfs(pool: &mut Pool, graph: &Graph) -> Vec<i32> {
-    let n = graph.node_count();
+    search_with(graph, &LexBfs, None, None, &mut Threads(pool)).order
+}
 
-    let mut sets: Vec<_> = vec![BTreeSet::new(); n];
-    let mut output = vec![0; n];
-    let mut numbered = vec![false; n];
+// The same search, but for LexDFS
+pub fn naive_lex_dfs(pool: &mut Pool, graph: &Graph) -> Vec<i32> {
+    naive_lex_dfs_with(pool, graph, None, None)
+}
 
-    for i in (0..n).rev() {
-        let max_set = select2(pool, &sets, &numbered);
+// With a start and a tie-break ordering, as in serial::lex_dfs_with
+pub fn naive_lex_dfs_with(
+    pool: &mut Pool,
+    graph: &Graph,
+    start: Option<u32>,
+    ties: Option<&[i32]>,
+) -> Vec<i32> {
+    search_with(graph, &LexDfs, start, ties, &mut Threads(pool)).order
+}
 
-        output[i] = max_set as i32;
-        numbered[max_set] = true;
+// Now, we're gonna catch the output from our naive lex-bfs
+// and test if it is a PES (EEP)
+// Tarjan and Yannakakis' test, as in the rayon one: the parents are found by chunks, and then
+// every vertex checks the later neighbours of its children against its marked neighbourhood
+// Each chunk keeps one array of marks, so this is O(n + m), plus O(n) per thread
+pub fn is_pes(pool: &mut Pool, scheme: &[i32], graph: &Graph) -> bool {
+    let n = graph.node_count();
+    if !is_scheme(scheme, n) {
+        return false;
+    }
 
-        let neighbors = graph.neighbors_slice(graph.from_index(max_set));
-        let chunk_size = (neighbors.len() / pool.thread_count() as usize) + 1;
+    let position = scheme_positions(scheme);
+    let chunk_size = (n / pool.thread_count() as usize) + 1;
 
-        pool.scoped(|scope| {
-            neighbors.chunks(chunk_size).for_each(|chunk| {
-                let numbered_ptr = MutSendable(NonNull::new(numbered.as_mut_ptr()).unwrap());
-                let sets_ptr = MutSendable(NonNull::new(sets.as_mut_ptr()).unwrap());
-                let chunk_ptr = Sendable(chunk.as_ptr());
-                let chunk_len = chunk.len();
-                scope.execute(move || unsafe {
-                    let chunk = slice::from_raw_parts(chunk_ptr.0, chunk_len);
-                    chunk
-                        .iter()
-                        .filter(|&neighbor| {
-                            let ptr = (numbered_ptr.0.as_ptr()).offset(*neighbor as isize);
-                            !*ptr
-                        })
-                        .for_each(|w| {
-                            let ptr = (sets_ptr.0.as_ptr()).offset(*w as isize);
-
-                            (*ptr).insert(Reverse(i));
-                        });
-                });
+    let mut parents = vec![None; n];
+    pool.scoped(|scope| {
+        for (index, chunk) in parents.chunks_mut(chunk_size).enumerate() {
+            let position = &position;
+            scope.execute(move || {
+                for (i, parent) in chunk.iter_mut().enumerate() {
+                    *parent = scheme_parent(graph, position, (index * chunk_size + i) as u32);
+                }
             });
-        });
-    }
-
-    output
-}
+        }
+    });
 
-fn filter_reduce_edge_count<T, I>(pool: &mut Pool, edges: &[T], maybe_clique: &HashSet<I>) -> usize
-where
-    I: Eq + Hash + Send + Sync,
-    T: EdgeRef<NodeId = I> + Send + Sync,
-{
     // filter
     let (sender, receiver) = unbounded();
-    let chunk_size = (edges.len() / pool.thread_count() as usize) + 1;
     pool.scoped(|scope| {
-        for chunk in edges.chunks(chunk_size) {
+        for chunk in scheme.chunks(chunk_size) {
             let sender = sender.clone();
+            let (position, parents) = (&position, &parents);
             scope.execute(move || {
-                chunk
+                let mut marked = vec![usize::MAX; n];
+                let chunk_ok = chunk
                     .iter()
-                    .filter(|x| {
-                        maybe_clique.contains(&(x.source())) && maybe_clique.contains(&(x.target()))
-                    })
-                    .for_each(|_| sender.send(1).unwrap());
+                    .all(|&p| children_covered(graph, position, parents, p as u32, &mut marked));
+
+                sender.send(chunk_ok).unwrap();
             });
         }
     });
 
     drop(sender);
-    let mut res: Vec<usize> = receiver.iter().collect();
-
-    if res.len() == 0 {
-        return 0;
-    }
-
-    while res.len() != 1 {
-        let (sender, receiver) = unbounded();
-        let chunk_size = (res.len() / pool.thread_count() as usize).max(2);
 
-        pool.scoped(|scope| {
-            for chunk in res.chunks(chunk_size) {
-                let sender = sender.clone();
-                scope.execute(move || {
-                    let local_count = chunk.iter().sum();
-
-                    sender.send(local_count).unwrap();
-                });
-            }
-        });
-
-        drop(sender);
-        res = receiver.iter().collect();
-    }
-
-    res[0] / 2
-}
-
-// Now, we're gonna catch the output from our naive lex-bfs
-// and test if it is a PES (EEP)
-pub fn is_pes(pool: &mut Pool, scheme: &[i32], graph: &Graph) -> bool {
-    let mut maybe_clique: HashSet<u32> = HashSet::with_capacity(scheme.len());
-    let mut eliminated_vertices: HashSet<u32> = HashSet::with_capacity(scheme.len());
-    let mut neighborhood = HashSet::with_capacity(scheme.len());
-    let mut edges = Vec::with_capacity(graph.edge_count());
-    for eliminated_vertex in scheme {
-        let eliminated_vertex = *eliminated_vertex as u32;
-        eliminated_vertices.insert(eliminated_vertex);
-
-        neighborhood.extend(graph.neighbors_slice(eliminated_vertex).iter().cloned());
-
-        maybe_clique.clear();
-        maybe_clique.extend(neighborhood.difference(&eliminated_vertices));
-
-        neighborhood.clear();
-
-        edges.clear();
-        edges.extend(graph.edge_references());
-
-        let subgraph_edge_count = filter_reduce_edge_count(pool, &edges, &maybe_clique);
-
-        if subgraph_edge_count != complete_graph_edge_count(maybe_clique.len()) {
-            return false;
-        }
-    }
-
-    true
+    // reduce
+    receiver.iter().all(|chunk_ok| chunk_ok)
 }
 
+#[allow(clippy::needless_borrow)]
 pub fn is_chordal(pool: &mut Pool, graph: &Graph) -> bool {
     let scheme = naive_lex_bfs(pool, &graph);
 
@@ -205,8 +158,11 @@ pub fn is_chordal(pool: &mut Pool, graph: &Graph) -> bool {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use crate::common::graph_from_edges;
+    use crate::serial::{is_lex_dfs, lex_bfs, lex_dfs, lex_dfs_with};
 
     #[test]
     fn diamond_graph_threads() {
@@ -304,4 +260,85 @@ mod tests {
 
         assert_eq!(is_pes(&mut pool, &res, &graph), true);
     }
+
+    #[test]
+    fn not_chordal_threads() {
+        let cpucount = num_cpus::get();
+        let mut pool = Pool::new(cpucount as u32);
+
+        let mut graph = Graph::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, a, ());
+
+        assert!(!is_chordal(&mut pool, &graph));
+    }
+
+    #[test]
+    fn lex_dfs_threads() {
+        let cpucount = num_cpus::get();
+        let mut pool = Pool::new(cpucount as u32);
+
+        let graph = graph_from_edges(
+            6,
+            vec![(0, 1), (0, 2), (1, 3), (2, 4), (3, 5), (4, 5), (1, 2)],
+        );
+
+        let res = naive_lex_dfs(&mut pool, &graph);
+
+        println!("{:?}", res);
+
+        assert!(is_lex_dfs(&res, &graph));
+        assert_eq!(res, lex_dfs(&graph));
+    }
+
+    #[test]
+    fn bad_schemes_threads() {
+        let cpucount = num_cpus::get();
+        let mut pool = Pool::new(cpucount as u32);
+
+        // a path 0 - 1 - 2
+        let graph = graph_from_edges(3, vec![(0, 1), (1, 2)]);
+
+        assert!(is_pes(&mut pool, &[0, 1, 2], &graph));
+        assert!(!is_pes(&mut pool, &[1, 0, 2], &graph));
+        assert!(!is_pes(&mut pool, &[0, 1], &graph));
+        assert!(!is_pes(&mut pool, &[0, 0, 2], &graph));
+    }
+
+    #[test]
+    fn lex_dfs_start_and_ties_threads() {
+        let cpucount = num_cpus::get();
+        let mut pool = Pool::new(cpucount as u32);
+
+        // a C4 0 - 1 - 2 - 3 with the chord 1 - 3, and a pendant 4 on 0, so most steps are ties
+        let graph = graph_from_edges(5, vec![(0, 1), (1, 2), (2, 3), (3, 0), (1, 3), (0, 4)]);
+        let orders: [&[i32]; 3] = [&[4, 3, 2, 1, 0], &[2, 0, 4, 1, 3], &[1, 4, 3, 0, 2]];
+
+        for start in (0..5).map(Some).chain(Some(None)) {
+            for ties in orders.iter().cloned().map(Some).chain(Some(None)) {
+                let res = naive_lex_dfs_with(&mut pool, &graph, start, ties);
+
+                assert_eq!(res, lex_dfs_with(&graph, start, ties));
+                assert!(is_lex_dfs(&res, &graph));
+            }
+        }
+    }
+
+    #[test]
+    fn same_ties_as_serial() {
+        let cpucount = num_cpus::get();
+        let mut pool = Pool::new(cpucount as u32);
+
+        let graph = graph_from_edges(5, vec![(0, 2), (1, 2), (2, 3), (2, 4), (3, 4)]);
+
+        assert_eq!(naive_lex_bfs(&mut pool, &graph), lex_bfs(&graph));
+    }
 }
@@ -0,0 +1,194 @@
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::common::{graph_from_edges, ForbiddenSubgraph};
+
+type Graph = Csr<(), (), Undirected>;
+
+// One step in building a threshold graph: adding a vertex either with no neighbours,
+// or adjacent to every vertex added before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Addition {
+    Isolated(u32),
+    Dominating(u32),
+}
+
+impl Addition {
+    pub fn vertex(self) -> u32 {
+        match self {
+            Addition::Isolated(v) | Addition::Dominating(v) => v,
+        }
+    }
+}
+
+// Chvátal and Hammer: G is a threshold graph iff it can be built from nothing by adding
+// isolated and dominating vertices, which we undo by peeling them off
+// Removing an isolated vertex changes no degree, and removing a dominating one takes one from
+// every degree, so we only count those instead of updating anything. With the vertices sorted
+// by degree, the one of lowest degree is the only candidate to be isolated, and the one of
+// highest degree to be dominating. This is O(n + m)
+// If neither is, what's left has an induced 2K2, C4 or P4, which we return
+pub fn creation_sequence(graph: &Graph) -> Result<Vec<Addition>, ForbiddenSubgraph> {
+    let n = graph.node_count();
+    let degree = |v: u32| graph.neighbors_slice(v).len();
+
+    // counting sort, by increasing degree
+    let mut by_degree = vec![Vec::new(); n];
+    for v in 0..n as u32 {
+        by_degree[degree(v)].push(v);
+    }
+    let sorted: Vec<u32> = by_degree.into_iter().flatten().collect();
+
+    let mut sequence = Vec::with_capacity(n);
+    let mut removed = vec![false; n];
+    let (mut low, mut high) = (0, n);
+    let mut dominating = 0;
+
+    while low < high {
+        let left = high - low;
+
+        if degree(sorted[low]) == dominating {
+            sequence.push(Addition::Isolated(sorted[low]));
+            removed[sorted[low] as usize] = true;
+            low += 1;
+        } else if degree(sorted[high - 1]) - dominating == left - 1 {
+            sequence.push(Addition::Dominating(sorted[high - 1]));
+            removed[sorted[high - 1] as usize] = true;
+            high -= 1;
+            dominating += 1;
+        } else {
+            return Err(obstruction(graph, &removed, sorted[high - 1]));
+        }
+    }
+
+    sequence.reverse();
+
+    Ok(sequence)
+}
+
+pub fn is_threshold(graph: &Graph) -> bool {
+    creation_sequence(graph).is_ok()
+}
+
+// Finds a 2K2, C4 or P4 among the vertices left, none of which is isolated or dominating,
+// given the one of highest degree, v
+// Some u misses v. If u has a neighbour w adjacent to v, then since d(v) >= d(w), some x ≠ w
+// adjacent to v misses w, and x - v - w - u is a P4, or a C4 if x and u are adjacent
+// Otherwise, take any neighbour w of u and y of v. Then v - y and w - u are a 2K2,
+// unless y and w are adjacent, making v - y - w - u a P4
+fn obstruction(graph: &Graph, removed: &[bool], v: u32) -> ForbiddenSubgraph {
+    let neighbours = |a: u32| {
+        graph
+            .neighbors_slice(a)
+            .iter()
+            .cloned()
+            .filter(move |&b| !removed[b as usize])
+    };
+    let adjacent = |a: u32, b: u32| graph.contains_edge(a, b);
+
+    let u = (0..graph.node_count() as u32)
+        .find(|&u| !removed[u as usize] && u != v && !adjacent(u, v))
+        .unwrap();
+
+    if let Some(w) = neighbours(u).find(|&w| adjacent(w, v)) {
+        let x = neighbours(v).find(|&x| x != w && !adjacent(x, w)).unwrap();
+
+        return if adjacent(x, u) {
+            ForbiddenSubgraph::C4([x, v, w, u])
+        } else {
+            ForbiddenSubgraph::P4([x, v, w, u])
+        };
+    }
+
+    let w = neighbours(u).next().unwrap();
+    let y = neighbours(v).next().unwrap();
+
+    if adjacent(y, w) {
+        ForbiddenSubgraph::P4([v, y, w, u])
+    } else {
+        ForbiddenSubgraph::TwoK2([v, y, w, u])
+    }
+}
+
+// The threshold graph built by a creation sequence, on the vertices it mentions
+pub fn graph_from_creation_sequence(sequence: &[Addition]) -> Graph {
+    let mut edges = Vec::new();
+
+    for (i, &addition) in sequence.iter().enumerate() {
+        if let Addition::Dominating(v) = addition {
+            edges.extend(sequence[..i].iter().map(|earlier| (earlier.vertex(), v)));
+        }
+    }
+
+    graph_from_edges(sequence.len(), edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::graph_from_reader;
+    use crate::test_util::{assert_induces, same_graph};
+    use std::fs::File;
+    use std::io::BufReader;
+
+    #[test]
+    fn round_trip() {
+        use Addition::*;
+
+        let sequence = vec![
+            Isolated(3),
+            Isolated(0),
+            Dominating(5),
+            Isolated(1),
+            Isolated(6),
+            Dominating(2),
+            Isolated(4),
+            Dominating(7),
+        ];
+        let graph = graph_from_creation_sequence(&sequence);
+        let found = creation_sequence(&graph).unwrap();
+
+        println!("{:?}", found);
+
+        assert!(matches!(found[0], Isolated(_)));
+        assert!(same_graph(&graph_from_creation_sequence(&found), &graph));
+    }
+
+    #[test]
+    fn complete_graph() {
+        let file = File::open("k10.txt").unwrap();
+
+        let graph = graph_from_reader(BufReader::new(file)).unwrap();
+        let sequence = creation_sequence(&graph).unwrap();
+
+        assert_eq!(sequence.len(), 10);
+        assert!(sequence[1..]
+            .iter()
+            .all(|addition| matches!(addition, Addition::Dominating(_))));
+    }
+
+    #[test]
+    fn not_threshold() {
+        let path = graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3)]);
+        match creation_sequence(&path) {
+            Err(ForbiddenSubgraph::P4(p)) => assert_induces(&path, &p, &[(0, 1), (1, 2), (2, 3)]),
+            other => panic!("expected a P4, got {:?}", other),
+        }
+
+        let cycle = graph_from_edges(4, vec![(0, 1), (1, 2), (2, 3), (3, 0)]);
+        match creation_sequence(&cycle) {
+            Err(ForbiddenSubgraph::C4(c)) => {
+                assert_induces(&cycle, &c, &[(0, 1), (1, 2), (2, 3), (3, 0)])
+            }
+            other => panic!("expected a C4, got {:?}", other),
+        }
+
+        // two edges, plus a vertex dominating them, which gets peeled off first
+        let two_k2 = graph_from_edges(5, vec![(0, 1), (2, 3), (4, 0), (4, 1), (4, 2), (4, 3)]);
+        match creation_sequence(&two_k2) {
+            Err(ForbiddenSubgraph::TwoK2(e)) => assert_induces(&two_k2, &e, &[(0, 1), (2, 3)]),
+            other => panic!("expected a 2K2, got {:?}", other),
+        }
+        assert!(!is_threshold(&two_k2));
+    }
+}
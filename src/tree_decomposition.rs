@@ -0,0 +1,480 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::clique_tree::CliqueTree;
+use crate::common::NotChordal;
+
+type Graph = Csr<(), (), Undirected>;
+
+// A tree decomposition, with bags and tree edges indexed from 0
+// Unlike a clique tree, this is always a single tree, as required by the PACE format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeDecomposition {
+    vertex_count: usize,
+    bags: Vec<Vec<u32>>,
+    edges: Vec<(usize, usize)>,
+}
+
+// The reasons a tree decomposition might not be one for a given graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidDecomposition {
+    VertexCountMismatch { expected: usize, found: usize },
+    VertexOutOfRange { bag: usize, vertex: u32 },
+    NotATree,
+    UncoveredVertex(u32),
+    UncoveredEdge(u32, u32),
+    // the bags containing this vertex don't induce a subtree
+    DisconnectedVertex(u32),
+}
+
+impl fmt::Display for InvalidDecomposition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidDecomposition::VertexCountMismatch { expected, found } => write!(
+                f,
+                "decomposition has {} vertices, but the graph has {}",
+                found, expected
+            ),
+            InvalidDecomposition::VertexOutOfRange { bag, vertex } => {
+                write!(f, "bag {} has out of range vertex {}", bag, vertex)
+            }
+            InvalidDecomposition::NotATree => write!(f, "decomposition is not a tree"),
+            InvalidDecomposition::UncoveredVertex(v) => write!(f, "vertex {} is in no bag", v),
+            InvalidDecomposition::UncoveredEdge(v, w) => {
+                write!(f, "edge {}-{} is in no bag", v, w)
+            }
+            InvalidDecomposition::DisconnectedVertex(v) => {
+                write!(f, "bags containing vertex {} are not connected", v)
+            }
+        }
+    }
+}
+
+impl Error for InvalidDecomposition {}
+
+impl TreeDecomposition {
+    // For a chordal graph, the maximal cliques give an optimal tree decomposition
+    // Any other graph can be decomposed by passing one of its triangulations instead
+    pub fn new(chordal: &Graph) -> Result<TreeDecomposition, NotChordal> {
+        let tree = CliqueTree::new(chordal)?;
+
+        Ok(TreeDecomposition::from_clique_tree(
+            &tree,
+            chordal.node_count(),
+        ))
+    }
+
+    // The roots of a clique forest are chained together, as their separators are empty
+    pub fn from_clique_tree(tree: &CliqueTree, vertex_count: usize) -> TreeDecomposition {
+        let bags = tree.cliques().map(|clique| clique.to_vec()).collect();
+        let mut edges: Vec<_> = tree
+            .separators()
+            .map(|(clique, parent, _)| (clique, parent))
+            .collect();
+
+        let roots: Vec<_> = tree.roots().collect();
+        edges.extend(roots.windows(2).map(|pair| (pair[0], pair[1])));
+
+        TreeDecomposition {
+            vertex_count,
+            bags,
+            edges,
+        }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    pub fn bags(&self) -> &[Vec<u32>] {
+        &self.bags
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    // Size of the largest bag minus one, with the empty decomposition having width -1
+    pub fn width(&self) -> isize {
+        self.bags
+            .iter()
+            .map(|bag| bag.len() as isize)
+            .max()
+            .unwrap_or(0)
+            - 1
+    }
+
+    // PACE .td format: a "s td <bags> <largest bag> <vertices>" header, one
+    // "b <bag> <vertices>..." line per bag, then one "<bag> <bag>" line per tree edge
+    // Everything is indexed from 1
+    pub fn write_td(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "s td {} {} {}",
+            self.bags.len(),
+            self.width() + 1,
+            self.vertex_count
+        )?;
+
+        for (index, bag) in self.bags.iter().enumerate() {
+            write!(writer, "b {}", index + 1)?;
+            for v in bag {
+                write!(writer, " {}", v + 1)?;
+            }
+            writeln!(writer)?;
+        }
+
+        for (a, b) in &self.edges {
+            writeln!(writer, "{} {}", a + 1, b + 1)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_td(reader: impl BufRead) -> Result<TreeDecomposition, Box<dyn Error>> {
+        let mut header = None;
+        // bags in the order they are given, as (index, vertices), only placed at the end so
+        // that nothing is allocated from the counts in the header alone
+        let mut given = Vec::new();
+        let mut edges = Vec::new();
+
+        for line_str in reader.lines() {
+            let line_str = line_str?;
+            let elements: Vec<_> = line_str.split_ascii_whitespace().collect();
+
+            match elements.first() {
+                None | Some(&"c") => continue,
+                Some(&"s") => {
+                    if header.is_some() {
+                        return Err(format!("second header: {}", line_str).into());
+                    }
+                    if elements.len() != 5 || elements[1] != "td" {
+                        return Err(format!("malformed header: {}", line_str).into());
+                    }
+
+                    let bag_count: usize = elements[2].parse()?;
+                    let largest_bag: usize = elements[3].parse()?;
+                    // vertices are u32s, so their count must fit in one too
+                    let vertex_count: u32 = elements[4].parse()?;
+                    header = Some((bag_count, largest_bag, vertex_count as usize));
+                }
+                Some(&"b") => {
+                    if elements.len() < 2 {
+                        return Err(format!("malformed bag: {}", line_str).into());
+                    }
+
+                    let (bag_count, _, vertex_count) = header.ok_or("bag before the header")?;
+                    let index = one_based(elements[1], bag_count)?;
+                    let bag = elements[2..]
+                        .iter()
+                        .map(|v| one_based(v, vertex_count).map(|v| v as u32))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    given.push((index, bag));
+                }
+                Some(_) => {
+                    if elements.len() != 2 {
+                        return Err(format!("malformed edge: {}", line_str).into());
+                    }
+
+                    let (bag_count, _, _) = header.ok_or("edge before the header")?;
+                    let a = one_based(elements[0], bag_count)?;
+                    let b = one_based(elements[1], bag_count)?;
+                    edges.push((a, b));
+                }
+            }
+        }
+
+        let (bag_count, largest_bag, vertex_count) = header.ok_or("missing header")?;
+        if given.len() != bag_count {
+            return Err(format!(
+                "header says there are {} bags, but {} are given",
+                bag_count,
+                given.len()
+            )
+            .into());
+        }
+
+        let mut bags = vec![None; bag_count];
+        for (index, bag) in given {
+            if bags[index].replace(bag).is_some() {
+                return Err(format!("bag {} defined twice", index + 1).into());
+            }
+        }
+        let bags = bags
+            .into_iter()
+            .enumerate()
+            .map(|(index, bag)| bag.ok_or_else(|| format!("bag {} is missing", index + 1)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let largest = bags.iter().map(Vec::len).max().unwrap_or(0);
+        if largest != largest_bag {
+            return Err(format!(
+                "header says the largest bag has {} vertices, but it has {}",
+                largest_bag, largest
+            )
+            .into());
+        }
+
+        Ok(TreeDecomposition {
+            vertex_count,
+            bags,
+            edges,
+        })
+    }
+
+    // Checks that this is a tree decomposition of the graph, returning its width
+    pub fn validate(&self, graph: &Graph) -> Result<isize, InvalidDecomposition> {
+        let n = graph.node_count();
+        if self.vertex_count != n {
+            return Err(InvalidDecomposition::VertexCountMismatch {
+                expected: n,
+                found: self.vertex_count,
+            });
+        }
+
+        self.validate_tree()?;
+
+        // bags containing each vertex, in increasing order
+        let mut bags_of = vec![Vec::new(); n];
+        for (index, bag) in self.bags.iter().enumerate() {
+            for &v in bag {
+                if v as usize >= n {
+                    return Err(InvalidDecomposition::VertexOutOfRange {
+                        bag: index,
+                        vertex: v,
+                    });
+                }
+
+                bags_of[v as usize].push(index);
+            }
+        }
+
+        if let Some(v) = (0..n).find(|&v| bags_of[v].is_empty()) {
+            return Err(InvalidDecomposition::UncoveredVertex(v as u32));
+        }
+
+        for v in 0..n as u32 {
+            for &w in graph.neighbors_slice(v).iter().filter(|&&w| v < w) {
+                if !intersects(&bags_of[v as usize], &bags_of[w as usize]) {
+                    return Err(InvalidDecomposition::UncoveredEdge(v, w));
+                }
+            }
+        }
+
+        // in a tree, the bags containing v are connected iff they have one less edge among them
+        let mut shared_edges = vec![0; n];
+        let mut in_bag = vec![usize::MAX; n];
+        for &(a, b) in &self.edges {
+            for &v in &self.bags[a] {
+                in_bag[v as usize] = a;
+            }
+
+            for &v in &self.bags[b] {
+                if in_bag[v as usize] == a {
+                    shared_edges[v as usize] += 1;
+                    // don't count the same vertex twice if it's repeated in the bag
+                    in_bag[v as usize] = usize::MAX;
+                }
+            }
+        }
+
+        for v in 0..n {
+            let mut bags = bags_of[v].clone();
+            bags.dedup();
+
+            if shared_edges[v] + 1 != bags.len() {
+                return Err(InvalidDecomposition::DisconnectedVertex(v as u32));
+            }
+        }
+
+        Ok(self.width())
+    }
+
+    fn validate_tree(&self) -> Result<(), InvalidDecomposition> {
+        let bag_count = self.bags.len();
+        if bag_count == 0 {
+            return if self.edges.is_empty() {
+                Ok(())
+            } else {
+                Err(InvalidDecomposition::NotATree)
+            };
+        }
+
+        if self.edges.len() + 1 != bag_count {
+            return Err(InvalidDecomposition::NotATree);
+        }
+
+        let mut adjacency = vec![Vec::new(); bag_count];
+        for &(a, b) in &self.edges {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+
+        // with one less edge than nodes, being connected is the same as being a tree
+        let mut seen = vec![false; bag_count];
+        let mut stack = vec![0];
+        seen[0] = true;
+        while let Some(a) = stack.pop() {
+            for &b in &adjacency[a] {
+                if !seen[b] {
+                    seen[b] = true;
+                    stack.push(b);
+                }
+            }
+        }
+
+        if seen.iter().all(|&seen| seen) {
+            Ok(())
+        } else {
+            Err(InvalidDecomposition::NotATree)
+        }
+    }
+}
+
+fn one_based(element: &str, bound: usize) -> Result<usize, Box<dyn Error>> {
+    let index: usize = element.parse()?;
+
+    if index == 0 || index > bound {
+        return Err(format!("index {} out of range", element).into());
+    }
+
+    Ok(index - 1)
+}
+
+// Whether two sorted slices have an element in common
+fn intersects(a: &[usize], b: &[usize]) -> bool {
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            return true;
+        } else if a[i] < b[j] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn gem_graph_round_trip() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(b, d, ());
+        graph.add_edge(b, e, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, e, ());
+        graph.add_edge(e, a, ());
+
+        let decomposition = TreeDecomposition::new(&graph).unwrap();
+        assert_eq!(decomposition.validate(&graph), Ok(2));
+
+        let mut output = Vec::new();
+        decomposition.write_td(&mut output).unwrap();
+
+        println!("{}", String::from_utf8_lossy(&output));
+
+        let read = TreeDecomposition::read_td(BufReader::new(output.as_slice())).unwrap();
+        assert_eq!(read, decomposition);
+    }
+
+    #[test]
+    fn forest_is_chained() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+
+        let decomposition = TreeDecomposition::new(&graph).unwrap();
+
+        assert_eq!(decomposition.bags().len(), 2);
+        assert_eq!(decomposition.edges().len(), 1);
+        assert_eq!(decomposition.validate(&graph), Ok(1));
+        assert!(decomposition.bags().iter().any(|bag| bag == &[c]));
+    }
+
+    #[test]
+    fn invalid_decompositions() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+
+        // a C4
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, a, ());
+
+        let read = |td: &str| TreeDecomposition::read_td(BufReader::new(td.as_bytes())).unwrap();
+
+        let valid = read("c triangulated by a-c\ns td 2 3 4\nb 1 1 2 3\nb 2 1 3 4\n1 2\n");
+        assert_eq!(valid.validate(&graph), Ok(2));
+
+        let uncovered = read("s td 2 3 4\nb 1 1 2 3\nb 2 2 3 4\n1 2\n");
+        assert_eq!(
+            uncovered.validate(&graph),
+            Err(InvalidDecomposition::UncoveredEdge(0, 3))
+        );
+
+        let disconnected = read("s td 3 3 4\nb 1 1 2 3\nb 2 2 3\nb 3 1 3 4\n1 2\n2 3\n");
+        assert_eq!(
+            disconnected.validate(&graph),
+            Err(InvalidDecomposition::DisconnectedVertex(0))
+        );
+
+        let cycle = read("s td 2 3 4\nb 1 1 2 3\nb 2 1 3 4\n1 2\n2 1\n");
+        assert_eq!(cycle.validate(&graph), Err(InvalidDecomposition::NotATree));
+
+        assert!(TreeDecomposition::read_td(BufReader::new("b 1 1\n".as_bytes())).is_err());
+    }
+
+    #[test]
+    fn malformed_headers() {
+        let read = |td: &str| TreeDecomposition::read_td(BufReader::new(td.as_bytes()));
+
+        assert!(read("s td 2 3 4\nb 1 1 2 3\nb 2 1 3 4\n1 2\n").is_ok());
+
+        // the largest bag doesn't match the header
+        assert!(read("s td 2 2 4\nb 1 1 2 3\nb 2 1 3 4\n1 2\n").is_err());
+        assert!(read("s td 2 4 4\nb 1 1 2 3\nb 2 1 3 4\n1 2\n").is_err());
+
+        // vertices beyond the count in the header
+        assert!(read("s td 2 3 4\nb 1 1 2 5\nb 2 1 3 4\n1 2\n").is_err());
+        assert!(read("s td 1 1 4\nb 1 4294967297\n").is_err());
+        assert!(read("s td 1 1 4294967297\nb 1 1\n").is_err());
+
+        // bag counts the lines don't back up, however large
+        assert!(read("s td 18446744073709551615 1 1\n").is_err());
+        assert!(read("s td 18446744073709551615 1 1\nb 1 1\n").is_err());
+        assert!(read("s td 3 3 4\nb 1 1 2 3\nb 2 1 3 4\n1 2\n").is_err());
+        // a bag given twice, and a second header
+        assert!(read("s td 2 3 4\nb 1 1 2 3\nb 1 1 3 4\n1 2\n").is_err());
+        assert!(read("s td 2 3 4\ns td 2 3 4\nb 1 1 2 3\nb 2 1 3 4\n1 2\n").is_err());
+    }
+}
@@ -0,0 +1,356 @@
+use petgraph::csr::Csr;
+use petgraph::Undirected;
+
+use crate::common::{graph_from_edges, is_scheme, scheme_positions};
+
+type Graph = Csr<(), (), Undirected>;
+
+const NIL: usize = usize::MAX;
+
+// A minimal elimination scheme, together with the fill edges it adds to the graph
+// Adding them gives a minimal triangulation: a chordal supergraph from which
+// no fill edge can be removed without losing chordality
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triangulation {
+    pub scheme: Vec<i32>,
+    // each fill edge once, as (smaller vertex, larger vertex)
+    pub fill: Vec<(u32, u32)>,
+}
+
+impl Triangulation {
+    // The triangulated graph, where the scheme is a PES
+    pub fn graph(&self, graph: &Graph) -> Graph {
+        let edges = (0..graph.node_count() as u32)
+            .flat_map(|v| graph.neighbors_slice(v).iter().map(move |&w| (v, w)))
+            .filter(|&(v, w)| v < w)
+            .chain(self.fill.iter().cloned());
+
+        graph_from_edges(graph.node_count(), edges)
+    }
+}
+
+// Rose, Tarjan and Lueker's LEX M
+// Like LexBFS, but a numbered vertex v also adds its number to the label of every u reachable
+// through unnumbered vertices whose labels are all smaller than the one of u. Every such u
+// that is not a neighbour of v gets a fill edge to it. This is O(nm)
+pub fn lex_m(graph: &Graph) -> Triangulation {
+    minimal_elimination(graph, true)
+}
+
+// Berry, Blair, Heggernes and Peyton's MCS-M
+// The same search as LEX M, but with MCS's weights instead of labels. This is O(nm)
+pub fn mcs_m(graph: &Graph) -> Triangulation {
+    minimal_elimination(graph, false)
+}
+
+// Both searches share everything but the label update
+// LEX M keeps its labels as ranks: comparing labels only needs their relative order,
+// and appending the current number to a label moves it just above the ones equal to it
+fn minimal_elimination(graph: &Graph, lexicographic: bool) -> Triangulation {
+    let n = graph.node_count();
+
+    let mut output = vec![0; n];
+    let mut fill = Vec::new();
+    let mut weight = vec![0; n];
+    let mut numbered = vec![false; n];
+
+    // reached[u] == i iff u was already reached when numbering the i-th vertex
+    let mut reached = vec![NIL; n];
+    // vertices still to be searched from, by the largest weight along their path
+    let mut reach: Vec<Vec<u32>> = vec![Vec::new(); n + 1];
+    let mut raised = vec![false; n];
+
+    for i in (0..n).rev() {
+        // "select"
+        let v = (0..n)
+            .filter(|&u| !numbered[u])
+            .max_by_key(|&u| weight[u])
+            .expect("output vector was empty");
+
+        // α(i) = v
+        output[i] = v as i32;
+        numbered[v] = true;
+        reached[v] = i;
+
+        for &u in graph.neighbors_slice(v as u32) {
+            let u = u as usize;
+            if !numbered[u] && reached[u] != i {
+                reached[u] = i;
+                raised[u] = true;
+                reach[weight[u]].push(u as u32);
+            }
+        }
+
+        // search through paths of increasing weight
+        for j in 0..reach.len() {
+            while let Some(y) = reach[j].pop() {
+                for &z in graph.neighbors_slice(y) {
+                    let z = z as usize;
+                    if numbered[z] || reached[z] == i {
+                        continue;
+                    }
+
+                    reached[z] = i;
+                    if weight[z] > j {
+                        // every vertex on the path is lighter than z
+                        raised[z] = true;
+                        reach[weight[z]].push(z as u32);
+                        fill.push((v.min(z) as u32, v.max(z) as u32));
+                    } else {
+                        reach[j].push(z as u32);
+                    }
+                }
+            }
+        }
+
+        // "update"
+        if lexicographic {
+            for u in (0..n).filter(|&u| !numbered[u]) {
+                weight[u] = 2 * weight[u] + raised[u] as usize;
+            }
+            compress_ranks(&mut weight, &numbered);
+        } else {
+            for u in (0..n).filter(|&u| raised[u]) {
+                weight[u] += 1;
+            }
+        }
+
+        raised.iter_mut().for_each(|raised| *raised = false);
+    }
+
+    Triangulation {
+        scheme: output,
+        fill,
+    }
+}
+
+// The outcome of eliminating the vertices of a graph in some order, each time joining the
+// neighbours left of the eliminated vertex, so that the order becomes a PES of the filled graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EliminationGame {
+    // each fill edge once, as (smaller vertex, larger vertex), sorted
+    pub fill: Vec<(u32, u32)>,
+    pub fill_count: usize,
+    // the largest front: a vertex with its neighbours left when it is eliminated, a clique of
+    // the filled graph, which is one more than the width of the matching tree decomposition
+    pub max_front: usize,
+}
+
+impl EliminationGame {
+    // The filled graph, where the scheme is a PES
+    // petgraph only builds an undirected Csr one edge at a time, each insertion shifting the
+    // edges and rows after it, so this costs O(m' (n + m')) and is left out of the game itself
+    pub fn filled(&self, graph: &Graph) -> Graph {
+        let edges = (0..graph.node_count() as u32)
+            .flat_map(|v| graph.neighbors_slice(v).iter().map(move |&w| (v, w)))
+            .filter(|&(v, w)| v < w)
+            .chain(self.fill.iter().cloned());
+
+        graph_from_edges(graph.node_count(), edges)
+    }
+}
+
+// Rose, Tarjan and Lueker's elimination game, as computed by Tarjan and Yannakakis, without
+// playing it: the later neighbours of v in the filled graph are those w for which some path
+// from v to w only goes through vertices eliminated before both. Going through the vertices
+// in elimination order, every earlier neighbour of w leads to w by following, from each
+// vertex x, its follower: the first later neighbour x got. Marking the vertices reached
+// stops each walk at the first vertex already joined to w, so every edge of the filled graph
+// is found once. The fill is then sorted by two bucket passes, so this is O(n + m'), m' being
+// the number of edges of the filled graph
+// Returns None if scheme is not an ordering of the vertices
+pub fn elimination_game(graph: &Graph, scheme: &[i32]) -> Option<EliminationGame> {
+    let n = graph.node_count();
+    if !is_scheme(scheme, n) {
+        return None;
+    }
+
+    let position = scheme_positions(scheme);
+
+    let mut follower = vec![NIL; n];
+    // joined[x] == i iff x already has an edge to the i-th vertex eliminated
+    let mut joined = vec![NIL; n];
+    let mut adjacent = vec![NIL; n];
+    let mut later = vec![0; n];
+    let mut fill = Vec::new();
+
+    for (i, &w) in scheme.iter().enumerate() {
+        let w = w as usize;
+        follower[w] = w;
+        joined[w] = i;
+
+        let neighbours = graph.neighbors_slice(w as u32);
+        neighbours.iter().for_each(|&v| adjacent[v as usize] = i);
+
+        for &v in neighbours.iter().filter(|&&v| position[v as usize] < i) {
+            let mut x = v as usize;
+            while joined[x] != i {
+                joined[x] = i;
+                later[x] += 1;
+
+                if adjacent[x] != i {
+                    fill.push((x.min(w) as u32, x.max(w) as u32));
+                }
+
+                x = follower[x];
+            }
+
+            if follower[x] == x {
+                follower[x] = w;
+            }
+        }
+    }
+
+    let fill = sort_edges(n, fill);
+
+    Some(EliminationGame {
+        fill_count: fill.len(),
+        max_front: later.iter().map(|&count| count + 1).max().unwrap_or(0),
+        fill,
+    })
+}
+
+// Sorts edges given as (smaller vertex, larger vertex), bucketing them by their larger vertex
+// and then, going through those in order, by their smaller one. This is O(n + m)
+fn sort_edges(n: usize, edges: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    let mut by_larger = vec![Vec::new(); n];
+    for (v, w) in edges {
+        by_larger[w as usize].push(v);
+    }
+
+    let mut by_smaller = vec![Vec::new(); n];
+    for (w, smaller) in by_larger.into_iter().enumerate() {
+        for v in smaller {
+            by_smaller[v as usize].push(w as u32);
+        }
+    }
+
+    by_smaller
+        .into_iter()
+        .enumerate()
+        .flat_map(|(v, larger)| larger.into_iter().map(move |w| (v as u32, w)))
+        .collect()
+}
+
+// Replaces the weights of unnumbered vertices by their ranks among the distinct weights
+fn compress_ranks(weight: &mut [usize], numbered: &[bool]) {
+    let bound = weight.len() * 2 + 2;
+    let mut present = vec![false; bound];
+
+    for u in (0..weight.len()).filter(|&u| !numbered[u]) {
+        present[weight[u]] = true;
+    }
+
+    let mut rank = vec![0; bound];
+    let mut next_rank = 0;
+    for (value, &present) in present.iter().enumerate() {
+        if present {
+            rank[value] = next_rank;
+            next_rank += 1;
+        }
+    }
+
+    for u in (0..weight.len()).filter(|&u| !numbered[u]) {
+        weight[u] = rank[weight[u]];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serial::{is_chordal, is_pes};
+
+    fn cycle(length: u32) -> Graph {
+        graph_from_edges(length as usize, (0..length).map(|v| (v, (v + 1) % length)))
+    }
+
+    #[test]
+    fn c4_lex_m() {
+        let graph = cycle(4);
+
+        let triangulation = lex_m(&graph);
+
+        println!("{:?}", triangulation);
+
+        assert_eq!(triangulation.fill.len(), 1);
+
+        let filled = triangulation.graph(&graph);
+        assert!(is_chordal(&filled));
+        assert!(is_pes(&triangulation.scheme, &filled));
+    }
+
+    #[test]
+    fn c6_mcs_m() {
+        let graph = cycle(6);
+
+        let triangulation = mcs_m(&graph);
+
+        // every minimal triangulation of a cycle adds n - 3 chords
+        assert_eq!(triangulation.fill.len(), 3);
+
+        let filled = triangulation.graph(&graph);
+        assert!(is_chordal(&filled));
+        assert!(is_pes(&triangulation.scheme, &filled));
+    }
+
+    #[test]
+    fn chordal_has_no_fill() {
+        let mut graph = Csr::new();
+
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, c, ());
+        graph.add_edge(b, d, ());
+        graph.add_edge(b, e, ());
+        graph.add_edge(c, d, ());
+        graph.add_edge(d, e, ());
+        graph.add_edge(e, a, ());
+
+        assert!(lex_m(&graph).fill.is_empty());
+        assert!(mcs_m(&graph).fill.is_empty());
+    }
+
+    #[test]
+    fn elimination_game_scores() {
+        let graph = cycle(6);
+
+        // eliminating every other vertex first joins the other three into a triangle
+        let game = elimination_game(&graph, &[0, 2, 4, 1, 3, 5]).unwrap();
+        println!("{:?}", game);
+
+        assert_eq!(game.fill, vec![(1, 3), (1, 5), (3, 5)]);
+        assert_eq!(game.fill_count, 3);
+        assert_eq!(game.max_front, 3);
+        assert!(is_pes(&[0, 2, 4, 1, 3, 5], &game.filled(&graph)));
+
+        // going around the cycle joins the vertex across to everything left
+        let game = elimination_game(&graph, &[0, 1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(game.fill, vec![(1, 5), (2, 5), (3, 5)]);
+        assert_eq!(game.max_front, 3);
+
+        // the game of a minimal triangulation's scheme adds exactly its fill
+        let triangulation = lex_m(&cycle(7));
+        let mut fill = triangulation.fill.clone();
+        fill.sort();
+        assert_eq!(
+            elimination_game(&cycle(7), &triangulation.scheme)
+                .unwrap()
+                .fill,
+            fill
+        );
+    }
+
+    #[test]
+    fn elimination_game_bad_schemes() {
+        let graph = cycle(3);
+
+        assert_eq!(elimination_game(&graph, &[0, 1, 7]), None);
+        assert_eq!(elimination_game(&graph, &[0, 0, 1]), None);
+        assert_eq!(elimination_game(&graph, &[0, 1]), None);
+    }
+}